@@ -9,15 +9,11 @@
 //!
 //! 2. Count the number of valid passwords according to the new policy.
 
-// TODO:
-// - Explore parsers, check parse_display;
-// - Improve error handling;
-
-use std::error::Error;
-use std::fmt;
-use std::str::FromStr;
-
-use crate::helpers::read;
+use crate::helpers::read::{
+    self,
+    scan::{self, ScanError},
+    FromStrRef, Input,
+};
 
 //--------------------------------------------------------------------
 // Password Data Structure
@@ -30,14 +26,14 @@ use crate::helpers::read;
 /// - the pass `String` does not contain more than 255 repetitions of the same
 ///   character.
 #[derive(Debug, PartialEq, Eq)]
-struct PasswordRecord {
+struct PasswordRecord<'a> {
     ch: char,
     lower: u8,
     upper: u8,
-    pass: String,
+    pass: &'a str,
 }
 
-impl PasswordRecord {
+impl PasswordRecord<'_> {
     /// Checks whether or not a password is valid according to the old policy.
     fn is_valid_old(&self) -> bool {
         let count = self.pass.chars().filter(|&c| c == self.ch).count() as u8;
@@ -61,53 +57,34 @@ impl PasswordRecord {
     }
 }
 
-impl FromStr for PasswordRecord {
-    type Err = Box<dyn Error>;
+impl<'a> FromStrRef<'a> for PasswordRecord<'a> {
+    type Err = ScanError;
 
     /// Converts a string slice into a struct of the type `PasswordRecord`.
     ///
     /// # Arguments
     ///
     /// `s` is of the pattern "min-max ch: pass", where the fields min and
-    /// max are `u8`, ch is a `char` and pass is a substring.
+    /// max are `u8`, ch is a `char` and pass is a substring of `s`.
     ///
     /// - An example is: "2-9 c: ccccccccc"
     ///
-    /// # Assumptions
+    /// # Errors
     ///
-    /// String slice `s` holds well structured data.
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut iter = s.split(' ');
-        let mut bounds = iter.next().ok_or(ParseDay02Error)?.split('-');
-        let min: u8 = bounds.next().ok_or(ParseDay02Error)?.parse()?;
-        let max: u8 = bounds.next().ok_or(ParseDay02Error)?.parse()?;
-        let letter = iter
-            .next()
-            .ok_or(ParseDay02Error)?
-            .chars()
-            .next()
-            .ok_or(ParseDay02Error)?;
-        let pass = iter.next().ok_or(ParseDay02Error)?.to_owned();
+    /// Returns a `ScanError` naming the field that could not be parsed.
+    fn from_str_ref(s: &'a str) -> Result<Self, Self::Err> {
+        // `pass` is borrowed from `s`, so it is kept as a raw field
+        let mut fields = scan::fields(s, "{min}-{max} {ch}: {pass}", 4)?;
+        let mut next = || fields.next().unwrap();
         Ok(PasswordRecord {
-            ch: letter,
-            lower: min,
-            upper: max,
-            pass,
+            lower: scan::field(&next()?)?,
+            upper: scan::field(&next()?)?,
+            ch: scan::field(&next()?)?,
+            pass: next()?.value,
         })
     }
 }
 
-#[derive(Debug)]
-struct ParseDay02Error;
-
-impl Error for ParseDay02Error {}
-
-impl fmt::Display for ParseDay02Error {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Error while parsing Day02 input. Malformed input data.")
-    }
-}
-
 //--------------------------------------------------------------------
 // Helpers
 //--------------------------------------------------------------------
@@ -129,8 +106,8 @@ fn count_valid_new(passwords: &[PasswordRecord]) -> usize {
 //--------------------------------------------------------------------
 
 pub fn run() {
-    let input = read::to_str("day02").unwrap();
-    let passwords = read::lines_into_vec(&input);
+    let input = Input::open("day02").unwrap();
+    let passwords = read::lines_into_vec_ref(input.as_str());
     println!("Day 02");
     println!(
         "Valid password count on old policy: {}",
@@ -153,23 +130,23 @@ mod tests {
     use lazy_static::lazy_static;
 
     lazy_static! {
-        static ref P1: PasswordRecord = PasswordRecord {
+        static ref P1: PasswordRecord<'static> = PasswordRecord {
             lower: 1,
             upper: 3,
             ch: 'a',
-            pass: "abcde".to_owned(),
+            pass: "abcde",
         };
-        static ref P2: PasswordRecord = PasswordRecord {
+        static ref P2: PasswordRecord<'static> = PasswordRecord {
             lower: 1,
             upper: 3,
             ch: 'b',
-            pass: "cdefg".to_owned(),
+            pass: "cdefg",
         };
-        static ref P3: PasswordRecord = PasswordRecord {
+        static ref P3: PasswordRecord<'static> = PasswordRecord {
             lower: 2,
             upper: 9,
             ch: 'c',
-            pass: "ccccccccc".to_owned(),
+            pass: "ccccccccc",
         };
     }
 
@@ -178,9 +155,9 @@ mod tests {
         let s1 = "1-3 a: abcde";
         let s2 = "1-3 b: cdefg";
         let s3 = "2-9 c: ccccccccc";
-        assert_eq!(s1.parse::<PasswordRecord>().unwrap(), *P1);
-        assert_eq!(s2.parse::<PasswordRecord>().unwrap(), *P2);
-        assert_eq!(s3.parse::<PasswordRecord>().unwrap(), *P3);
+        assert_eq!(PasswordRecord::from_str_ref(s1).unwrap(), *P1);
+        assert_eq!(PasswordRecord::from_str_ref(s2).unwrap(), *P2);
+        assert_eq!(PasswordRecord::from_str_ref(s3).unwrap(), *P3);
     }
 
     #[test]
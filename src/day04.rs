@@ -1,24 +1,26 @@
-use std::{error::Error, str::FromStr};
-
 // todo: test all individual closure conditions in `is_valid`
-use crate::helpers::read;
+use crate::helpers::read::{
+    self,
+    parse::{Cursor, ParseError},
+    FromStrRef, Input,
+};
 
 #[derive(Debug, Default, PartialEq, Eq, Clone)]
-struct Passport {
-    byr: Option<String>, // birth year
-    iyr: Option<String>, // issue year
-    eyr: Option<String>, // expiration year
-    cid: Option<String>, // country id
-    pid: Option<String>, // passport id
-    hgt: Option<String>, // height
-    hcl: Option<String>, // hair colour
-    ecl: Option<String>, // eye colour
+struct Passport<'a> {
+    byr: Option<&'a str>, // birth year
+    iyr: Option<&'a str>, // issue year
+    eyr: Option<&'a str>, // expiration year
+    cid: Option<&'a str>, // country id
+    pid: Option<&'a str>, // passport id
+    hgt: Option<&'a str>, // height
+    hcl: Option<&'a str>, // hair colour
+    ecl: Option<&'a str>, // eye colour
 }
 
-impl Passport {
+impl<'a> Passport<'a> {
     /// Panics if key cannot be matched.
-    fn add_key(&mut self, key: &str, val: &str) {
-        let value = || -> Option<String> { Some(val.to_owned()) };
+    fn add_key(&mut self, key: &str, val: &'a str) {
+        let value = || -> Option<&'a str> { Some(val) };
 
         match key {
             "byr" => self.byr = value(),
@@ -55,7 +57,7 @@ impl Passport {
         };
 
         // closure to validate year values
-        let check_year_range = |val: &Option<String>, min: u16, max: u16| -> bool {
+        let check_year_range = |val: &Option<&str>, min: u16, max: u16| -> bool {
             if let Some(str) = val {
                 if str.chars().count() != 4 {
                     return false;
@@ -151,15 +153,11 @@ impl Passport {
     }
 }
 
-impl FromStr for Passport {
-    type Err = Box<dyn Error>;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+impl<'a> FromStrRef<'a> for Passport<'a> {
+    type Err = ParseError;
+    fn from_str_ref(s: &'a str) -> Result<Self, Self::Err> {
         let mut passport = Passport::default();
-        let split_at: &[_] = &[' ', ':', '\n'];
-        let mut split = s.split(split_at).peekable();
-        while split.peek().is_some() {
-            let key = split.next().unwrap();
-            let val = split.next().expect("Key without value.");
+        for (key, val) in Cursor::new(s).key_values(":")? {
             passport.add_key(key, val);
         }
         Ok(passport)
@@ -179,8 +177,8 @@ fn count_valid_complete(passports: &[Passport]) -> usize {
 //--------------------------------------------------------------------
 
 pub fn run() {
-    let input = read::to_str("day04").unwrap();
-    let passports = read::split_into_vec(&input, "\n\n");
+    let input = Input::open("day04").unwrap();
+    let passports = read::records_into_vec_ref(input.as_str());
     println!("Day 04");
     println!(
         "Total of valid passports by simple method: {}",
@@ -243,57 +241,68 @@ eyr:2022
 iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719";
 
     lazy_static! {
-        static ref INPUT: Vec<Passport> = read::split_into_vec(INPUT_STR0, "\n\n");
-        static ref INPUT_INVALID: Vec<Passport> = read::split_into_vec(INPUT_STR_INVALID, "\n\n");
-        static ref INPUT_VALID: Vec<Passport> = read::split_into_vec(INPUT_STR_VALID, "\n\n");
+        static ref INPUT: Vec<Passport<'static>> = read::records_into_vec_ref(INPUT_STR0);
+        static ref INPUT_INVALID: Vec<Passport<'static>> =
+            read::records_into_vec_ref(INPUT_STR_INVALID);
+        static ref INPUT_VALID: Vec<Passport<'static>> =
+            read::records_into_vec_ref(INPUT_STR_VALID);
     }
 
     #[test]
     fn test_parse_input() {
         let parsed = vec![
             Passport {
-                ecl: Some("gry".to_owned()),
-                pid: Some("860033327".to_owned()),
-                eyr: Some("2020".to_owned()),
-                hcl: Some("#fffffd".to_owned()),
-                byr: Some("1937".to_owned()),
-                iyr: Some("2017".to_owned()),
-                cid: Some("147".to_owned()),
-                hgt: Some("183cm".to_owned()),
+                ecl: Some("gry"),
+                pid: Some("860033327"),
+                eyr: Some("2020"),
+                hcl: Some("#fffffd"),
+                byr: Some("1937"),
+                iyr: Some("2017"),
+                cid: Some("147"),
+                hgt: Some("183cm"),
             },
             Passport {
-                iyr: Some("2013".to_owned()),
-                ecl: Some("amb".to_owned()),
-                cid: Some("350".to_owned()),
-                eyr: Some("2023".to_owned()),
-                pid: Some("028048884".to_owned()),
-                hcl: Some("#cfa07d".to_owned()),
-                byr: Some("1929".to_owned()),
+                iyr: Some("2013"),
+                ecl: Some("amb"),
+                cid: Some("350"),
+                eyr: Some("2023"),
+                pid: Some("028048884"),
+                hcl: Some("#cfa07d"),
+                byr: Some("1929"),
                 ..Passport::default()
             },
             Passport {
-                hcl: Some("#ae17e1".to_owned()),
-                iyr: Some("2013".to_owned()),
-                eyr: Some("2024".to_owned()),
-                ecl: Some("brn".to_owned()),
-                pid: Some("760753108".to_owned()),
-                byr: Some("1931".to_owned()),
-                hgt: Some("179cm".to_owned()),
+                hcl: Some("#ae17e1"),
+                iyr: Some("2013"),
+                eyr: Some("2024"),
+                ecl: Some("brn"),
+                pid: Some("760753108"),
+                byr: Some("1931"),
+                hgt: Some("179cm"),
                 ..Passport::default()
             },
             Passport {
-                hcl: Some("#cfa07d".to_owned()),
-                eyr: Some("2025".to_owned()),
-                pid: Some("166559648".to_owned()),
-                iyr: Some("2011".to_owned()),
-                ecl: Some("brn".to_owned()),
-                hgt: Some("59in".to_owned()),
+                hcl: Some("#cfa07d"),
+                eyr: Some("2025"),
+                pid: Some("166559648"),
+                iyr: Some("2011"),
+                ecl: Some("brn"),
+                hgt: Some("59in"),
                 ..Passport::default()
             },
         ];
         assert_eq!(parsed, *INPUT);
     }
 
+    #[test]
+    fn test_crlf_input() {
+        // as read through `Input`, which leaves `\r\n` line endings in place
+        let crlf = INPUT_STR0.replace('\n', "\r\n") + "\r\n";
+        let passports: Vec<Passport> = read::records_into_vec_ref(&crlf);
+        assert_eq!(passports, *INPUT);
+        assert_eq!(count_valid_simple(&passports), 2);
+    }
+
     #[test]
     fn test_add_key() {
         let mut p = Passport::default();
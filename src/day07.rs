@@ -1,37 +1,61 @@
 // todo: finish tests
 
-use crate::helpers::{graph::Graph, read};
-
+use crate::helpers::{
+    graph::{Cycle, EdgeDirection, Graph},
+    read::{
+        self,
+        parse::{self, Cursor, ParseError},
+    },
+};
+
+/// Parses the bag rules into a graph where each bag points to the bags it
+/// contains, weighted by their quantity. Bag names are the two words of their
+/// colour joined together, e.g. "shinygold".
+///
+/// # Panics
+///
+/// Panics if a rule is malformed.
 fn into_graph(input: &str) -> Graph {
     let mut ret = Graph::new();
-    let lines = input.lines();
-    for line in lines {
-        let mut iter = line.split_ascii_whitespace();
-        let vertex0: String = iter.by_ref().take(2).collect();
-        let mut inner = iter.skip(2).peekable();
-        let mut vertices = Vec::<(u32, String)>::new();
-        while inner.peek().is_some() {
-            let weight = inner.next().unwrap();
-            if weight == "no" {
-                break;
-            }
-            let edge: (u32, String) = (
-                weight.parse().expect("Error parsing an edge weight."),
-                inner.by_ref().take(2).collect(),
-            );
-            vertices.push(edge);
-            inner.next();
-        }
+    let rules = parse::lines(input, rule).expect("Error parsing the bag rules.");
+    for (vertex0, vertices) in rules {
         ret.add_edges(&vertex0, &vertices);
     }
     ret
 }
 
+/// parses a rule such as "light red bags contain 1 bright white bag, 2 muted
+/// yellow bags." or "faded blue bags contain no other bags.".
+fn rule(c: &mut Cursor) -> Result<(String, Vec<(u32, String)>), ParseError> {
+    let vertex0 = colour(c)?;
+    c.literal(" bags contain ")?;
+    let vertices = match c.attempt(|c| c.literal("no other bags")) {
+        Some(_) => Vec::new(),
+        None => c.separated(", ", |c| {
+            let weight = c.integer()?;
+            c.literal(" ")?;
+            let vertex = colour(c)?;
+            c.literal(" bag")?;
+            c.attempt(|c| c.literal("s"));
+            Ok((weight, vertex))
+        })?,
+    };
+    c.literal(".")?;
+    Ok((vertex0, vertices))
+}
+
+/// parses the two words of a bag colour and joins them.
+fn colour(c: &mut Cursor) -> Result<String, ParseError> {
+    let adjective = c.word()?;
+    c.literal(" ")?;
+    Ok(adjective.to_owned() + c.word()?)
+}
+
 fn count_bags_holding(graph: &Graph, holding: &str) -> usize {
     graph.list_ancestors(holding).iter().count()
 }
 
-fn count_bags_inside(graph: &Graph, inside_of: &str) -> u32 {
+fn count_bags_inside(graph: &Graph, inside_of: &str) -> Result<u32, Cycle> {
     graph.weigh_successors(inside_of)
 }
 
@@ -44,7 +68,7 @@ pub fn run() {
     let input = read::to_str("day07").expect("Error reading day07 input file");
     let graph = into_graph(&input);
     let ch = count_bags_holding(&graph, "shinygold");
-    let ci = count_bags_inside(&graph, "shinygold");
+    let ci = count_bags_inside(&graph, "shinygold").expect("Error: bags contain themselves");
     println!(
         "Number of bags containing at least one Shiny Gold bag: {}",
         ch
@@ -52,6 +76,13 @@ pub fn run() {
     println!("Number of bags inside a Shiny Gold bag: {}", ci);
 }
 
+/// Returns the bag rules as a DOT document, highlighting the bags that can
+/// hold a Shiny Gold bag.
+pub fn dot() -> String {
+    let input = read::to_str("day07").expect("Error reading day07 input file");
+    into_graph(&input).to_dot_highlighting("shinygold", EdgeDirection::Incoming)
+}
+
 //--------------------------------------------------------------------
 // Tests
 //--------------------------------------------------------------------
@@ -115,6 +146,13 @@ dark violet bags contain no other bags.";
         assert_eq!(count_bags_holding(&parsed_graph1, SHINY_GOLD), 4);
 
         let parsed_graph2 = into_graph(INPUT_STR2);
-        assert_eq!(count_bags_inside(&parsed_graph2, SHINY_GOLD), 126);
+        assert_eq!(count_bags_inside(&parsed_graph2, SHINY_GOLD), Ok(126));
+
+        // A bag can not contain itself, even indirectly
+        let cyclic_graph = into_graph(
+            "light red bags contain 1 dark red bag.\ndark red bags contain 2 light red bags.",
+        );
+        let cycle = count_bags_inside(&cyclic_graph, "lightred").unwrap_err();
+        assert_eq!(cycle.vertices, ["lightred", "darkred"]);
     }
 }
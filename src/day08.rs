@@ -229,15 +229,13 @@ impl DebugInstruction {
 impl FromStr for DebugInstruction {
     type Err = Box<dyn Error>;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut iter = s.split_ascii_whitespace();
-        let op = iter.next().ok_or("Could not get Instruction code.")?;
-        let op = match op {
+        let (op, arg) = read::scan!(s, "{op} {arg}", String, i16)?;
+        let op = match op.as_str() {
             "acc" => Op::Acc,
             "jmp" => Op::Jmp,
             "nop" => Op::Nop,
-            _ => panic!("Wrong Instruction code in input."),
+            _ => return Err(format!("Wrong Instruction code '{}' in input.", op).into()),
         };
-        let arg: i16 = iter.next().unwrap().parse()?;
         Ok(DebugInstruction::new((op, arg)))
     }
 }
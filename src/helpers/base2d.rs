@@ -1,10 +1,22 @@
 use std::{
     convert::{TryFrom, TryInto},
     error::Error,
+    fmt,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
 };
 
 /// Helper struct for representing 2d values, i.e: coordinates, indexes, etc.
-#[derive(Copy, Clone, Debug)]
+///
+/// Supports component-wise addition and subtraction, scaling by a scalar and
+/// negation. Ordering compares `x` first and then `y`.
+///
+/// ```
+/// use adv20::helpers::base2d::Base2d;
+/// let p = Base2d::new(1i64, -2) + Base2d::new(3, 4) * 2;
+/// assert_eq!(p, Base2d::new(7, 6));
+/// assert_eq!(p.manhattan(Base2d::new(0, 0)), 13);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Base2d<U> {
     pub x: U,
     pub y: U,
@@ -25,20 +37,299 @@ where
     }
 }
 
-/// Defines how to convert a tuple (U, U) to a Base2d. In practice, allows to
-/// use tuples, in some situations, as a more handy alternative instead of
-/// instaciating the Base2d struct.
-impl<U> TryFrom<(U, U)> for Base2d<usize>
+impl<U> Base2d<U>
 where
-    U: TryInto<usize>,
-    <U as TryInto<usize>>::Error: std::error::Error + 'static,
+    U: Copy + Ord + Sub<Output = U> + Add<Output = U>,
 {
-    type Error = Box<dyn Error>;
+    /// returns the manhattan (taxicab) distance between `self` and `other`.
+    pub fn manhattan(&self, other: Base2d<U>) -> U {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+    }
+
+    /// returns the chebyshev (chessboard) distance between `self` and
+    /// `other`, i.e. the largest difference between the coordinates.
+    pub fn chebyshev(&self, other: Base2d<U>) -> U {
+        abs_diff(self.x, other.x).max(abs_diff(self.y, other.y))
+    }
+}
+
+impl<U> Base2d<U>
+where
+    U: Copy + Neg<Output = U>,
+{
+    /// rotates the vector 90 degrees clockwise around the origin.
+    ///
+    /// The `y` axis points down, as rows do in a `Grid`, so clockwise turns
+    /// `(1, 0)` (east) into `(0, 1)` (south).
+    pub fn rotate_right(self) -> Base2d<U> {
+        Base2d::new(-self.y, self.x)
+    }
+
+    /// rotates the vector 90 degrees counterclockwise around the origin.
+    pub fn rotate_left(self) -> Base2d<U> {
+        Base2d::new(self.y, -self.x)
+    }
+
+    /// rotates the vector around the origin by `degrees`, clockwise if
+    /// positive and counterclockwise if negative.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `degrees` is not a multiple of 90.
+    pub fn rotate(self, degrees: i32) -> Base2d<U> {
+        assert!(
+            degrees % 90 == 0,
+            "Rotation must be a multiple of 90 degrees."
+        );
+        match (degrees / 90).rem_euclid(4) {
+            0 => self,
+            1 => self.rotate_right(),
+            2 => -self,
+            _ => self.rotate_left(),
+        }
+    }
+}
+
+/// absolute difference that does not underflow for unsigned types.
+fn abs_diff<U: Ord + Sub<Output = U>>(a: U, b: U) -> U {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+//--------------------------------------------------------------------
+// Arithmetic
+//--------------------------------------------------------------------
+
+impl<U: Add<Output = U>> Add for Base2d<U> {
+    type Output = Base2d<U>;
+
+    fn add(self, other: Base2d<U>) -> Base2d<U> {
+        Base2d {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl<U: AddAssign> AddAssign for Base2d<U> {
+    fn add_assign(&mut self, other: Base2d<U>) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl<U: Sub<Output = U>> Sub for Base2d<U> {
+    type Output = Base2d<U>;
+
+    fn sub(self, other: Base2d<U>) -> Base2d<U> {
+        Base2d {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+}
+
+impl<U: SubAssign> SubAssign for Base2d<U> {
+    fn sub_assign(&mut self, other: Base2d<U>) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+/// Scales both coordinates by `scalar`.
+impl<U: Mul<Output = U> + Copy> Mul<U> for Base2d<U> {
+    type Output = Base2d<U>;
+
+    fn mul(self, scalar: U) -> Base2d<U> {
+        Base2d {
+            x: self.x * scalar,
+            y: self.y * scalar,
+        }
+    }
+}
+
+impl<U: Neg<Output = U>> Neg for Base2d<U> {
+    type Output = Base2d<U>;
+
+    fn neg(self) -> Base2d<U> {
+        Base2d {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+//--------------------------------------------------------------------
+// Conversions
+//--------------------------------------------------------------------
+
+/// Converts a tuple `(x, y)` into a Base2d of the same type. In practice,
+/// allows to use tuples, in some situations, as a more handy alternative
+/// instead of instaciating the Base2d struct.
+impl<U> From<(U, U)> for Base2d<U> {
+    fn from(item: (U, U)) -> Self {
+        Base2d {
+            x: item.0,
+            y: item.1,
+        }
+    }
+}
+
+impl From<(u8, u8)> for Base2d<usize> {
+    fn from(item: (u8, u8)) -> Self {
+        Base2d::new(item.0.into(), item.1.into())
+    }
+}
+
+impl From<(u16, u16)> for Base2d<usize> {
+    fn from(item: (u16, u16)) -> Self {
+        Base2d::new(item.0.into(), item.1.into())
+    }
+}
+
+/// Implements the fallible conversions from tuples of integer types that may
+/// not fit in a `usize`, e.g. negative values.
+macro_rules! try_from_tuple {
+    ($($t:ty),*) => {$(
+        impl TryFrom<($t, $t)> for Base2d<usize> {
+            type Error = TryFromBase2dError;
+
+            fn try_from(item: ($t, $t)) -> Result<Self, Self::Error> {
+                convert(item.0, item.1)
+            }
+        }
+    )*};
+}
+
+try_from_tuple!(i8, i16, i32, i64, i128, isize, u32, u64, u128);
+
+/// Fails if any coordinate is negative.
+impl TryFrom<Base2d<i64>> for Base2d<usize> {
+    type Error = TryFromBase2dError;
+
+    fn try_from(item: Base2d<i64>) -> Result<Self, Self::Error> {
+        convert(item.x, item.y)
+    }
+}
+
+/// Fails if any coordinate does not fit in an `i64`.
+impl TryFrom<Base2d<usize>> for Base2d<i64> {
+    type Error = TryFromBase2dError;
+
+    fn try_from(item: Base2d<usize>) -> Result<Self, Self::Error> {
+        convert(item.x, item.y)
+    }
+}
+
+/// converts both coordinates, reporting the first one out of range.
+fn convert<T, V>(x: T, y: T) -> Result<Base2d<V>, TryFromBase2dError>
+where
+    T: TryInto<V>,
+{
+    Ok(Base2d {
+        x: x.try_into().map_err(|_| TryFromBase2dError { axis: 'x' })?,
+        y: y.try_into().map_err(|_| TryFromBase2dError { axis: 'y' })?,
+    })
+}
+
+/// Error returned when a coordinate does not fit in the target type of a
+/// conversion, for example a negative value converted to `usize`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TryFromBase2dError {
+    /// the offending coordinate, `'x'` or `'y'`.
+    pub axis: char,
+}
+
+impl Error for TryFromBase2dError {}
+
+impl fmt::Display for TryFromBase2dError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Coordinate {} out of range for the target type.",
+            self.axis
+        )
+    }
+}
+
+//--------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Base2d::new(3i64, -4);
+        let b = Base2d::new(-1i64, 2);
+        assert_eq!(a + b, Base2d::new(2, -2));
+        assert_eq!(a - b, Base2d::new(4, -6));
+        assert_eq!(a * 3, Base2d::new(9, -12));
+        assert_eq!(-a, Base2d::new(-3, 4));
+        let mut c = a;
+        c += b;
+        c -= b * 2;
+        assert_eq!(c, a - b);
+    }
+
+    #[test]
+    fn test_rotation() {
+        let waypoint = Base2d::new(10i64, -4); // 10 east, 4 north
+        assert_eq!(waypoint.rotate_right(), Base2d::new(4, 10));
+        assert_eq!(waypoint.rotate_left(), Base2d::new(-4, -10));
+        assert_eq!(waypoint.rotate(180), -waypoint);
+        assert_eq!(waypoint.rotate(-90), waypoint.rotate(270));
+        assert_eq!(waypoint.rotate(720), waypoint);
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Base2d::new(1usize, 8);
+        let b = Base2d::new(4usize, 2);
+        assert_eq!(a.manhattan(b), 9);
+        assert_eq!(b.manhattan(a), 9);
+        assert_eq!(a.chebyshev(b), 6);
+    }
+
+    #[test]
+    fn test_ordering_and_hashing() {
+        assert!(Base2d::new(0, 5) < Base2d::new(1, 0));
+        assert!(Base2d::new(1, 0) < Base2d::new(1, 1));
+        let set: HashSet<Base2d<i64>> = [(0, 0), (1, 2), (0, 0)]
+            .iter()
+            .map(|&(x, y)| Base2d::new(x, y))
+            .collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_checked_conversions() {
+        let p = Base2d::new(3i64, 4);
+        assert_eq!(Base2d::<usize>::try_from(p).unwrap(), Base2d::new(3, 4));
+        assert!(Base2d::<usize>::try_from(Base2d::new(-1i64, 0)).is_err());
+        let q: Base2d<i64> = Base2d::new(7usize, 0).try_into().unwrap();
+        assert_eq!(q, Base2d::new(7, 0));
+        assert!(Base2d::<i64>::try_from(Base2d::new(usize::MAX, 0)).is_err());
+    }
 
-    fn try_from(item: (U, U)) -> Result<Self, Self::Error> {
-        Ok(Base2d {
-            x: item.0.try_into()?,
-            y: item.1.try_into()?,
-        })
+    #[test]
+    fn test_tuple_conversions() {
+        let p: Base2d<usize> = (1usize, 2usize).into();
+        assert_eq!(p, Base2d::new(1, 2));
+        assert_eq!(Base2d::<usize>::from((3u8, 4u8)), Base2d::new(3, 4));
+        assert_eq!(
+            Base2d::<usize>::try_from((5, 6)).unwrap(),
+            Base2d::new(5, 6)
+        );
+        assert_eq!(
+            Base2d::<usize>::try_from((0i64, -1i64)).unwrap_err(),
+            TryFromBase2dError { axis: 'y' }
+        );
     }
 }
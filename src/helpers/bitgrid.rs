@@ -0,0 +1,280 @@
+//! A two-dimensional grid of booleans packed into `u64` words.
+//!
+//! Mirrors the `Grid<bool>` API, but uses one bit per cell. Each row starts at
+//! a word boundary, so rows can be processed a word at a time: counting the
+//! cells set in a row is a sum of `count_ones`, and shifted copies of a row
+//! give the left and right neighbours of 64 cells in one operation.
+
+use super::grid::Grid;
+
+const BITS: usize = u64::BITS as usize;
+
+/// A bit packed two-dimensional array of booleans.
+///
+/// `x` is the column and `y` the row, as in `Grid`. Bit `x % 64` of the word
+/// `x / 64` of a row holds the cell at column `x`. Padding bits at the end of
+/// a row are always zero.
+///
+/// ```
+/// use adv20::helpers::bitgrid::BitGrid;
+/// let mut grid = BitGrid::new(100, 3, false);
+/// grid.set(70, 1, true);
+/// assert!(grid.get(70, 1));
+/// assert_eq!(grid.count_row(1), 1);
+/// assert_eq!(grid.count_neighbours(71, 2), 1);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BitGrid {
+    words: Vec<u64>,
+    words_per_row: usize,
+    pub len_x: usize,
+    pub len_y: usize,
+}
+
+impl BitGrid {
+    pub fn new(len_x: usize, len_y: usize, init: bool) -> BitGrid {
+        let words_per_row = len_x.div_ceil(BITS);
+        let mut grid = BitGrid {
+            words: vec![if init { !0 } else { 0 }; words_per_row * len_y],
+            words_per_row,
+            len_x,
+            len_y,
+        };
+        for y in 0..len_y {
+            grid.mask_row(y);
+        }
+        grid
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `flat` does not hold `len_x * len_y` elements.
+    pub fn new_with_vec(len_x: usize, len_y: usize, flat: Vec<bool>) -> BitGrid {
+        assert_eq!(flat.len(), len_x * len_y);
+        let mut grid = BitGrid::new(len_x, len_y, false);
+        for (i, &v) in flat.iter().enumerate() {
+            grid.set(i % len_x, i / len_x, v);
+        }
+        grid
+    }
+
+    /// returns the value at position x,y.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        let (word, bit) = self.index(x, y);
+        self.words[word] >> bit & 1 == 1
+    }
+
+    /// sets the value at position x,y.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds.
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        let (word, bit) = self.index(x, y);
+        if value {
+            self.words[word] |= 1 << bit;
+        } else {
+            self.words[word] &= !(1 << bit);
+        }
+    }
+
+    /// returns the total size of the array (len_x * len_y)
+    pub fn size(&self) -> usize {
+        self.len_x * self.len_y
+    }
+
+    /// returns the value at position `x, y`. Wraps around if either index is
+    /// larger than its array dimension.
+    pub fn wrap(&self, x: usize, y: usize) -> bool {
+        self.get(x % self.len_x, y % self.len_y)
+    }
+
+    /// returns the value at position `x, y`. If index `x` is larger than the x
+    /// dimension of the grid, the index is wrapped around.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `y` index is out of bounds.
+    pub fn wrap_x(&self, x: usize, y: usize) -> bool {
+        self.get(x % self.len_x, y)
+    }
+
+    /// returns the value at position `x, y`. If index `y` is larger than the y
+    /// dimmension of the grid, the index is wrapped around.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `x` index is out of bounds.
+    pub fn wrap_y(&self, x: usize, y: usize) -> bool {
+        self.get(x, y % self.len_y)
+    }
+
+    /// returns an iterator over the values of row `y`.
+    pub fn row(&self, y: usize) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len_x).map(move |x| self.get(x, y))
+    }
+
+    /// returns the packed words of row `y`.
+    pub fn row_words(&self, y: usize) -> &[u64] {
+        assert!(y < self.len_y, "Index out of bounds.");
+        let start = y * self.words_per_row;
+        &self.words[start..start + self.words_per_row]
+    }
+
+    /// returns the number of cells set in row `y`.
+    pub fn count_row(&self, y: usize) -> usize {
+        self.row_words(y)
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum()
+    }
+
+    /// returns the number of cells set in the whole grid.
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// returns the words of row `y` shifted by `dx` columns: bit `x` of the
+    /// output holds the cell at column `x + dx`. Columns falling outside the
+    /// grid read as `false`.
+    ///
+    /// For example, `shifted_row(y, -1)` holds the left neighbour of every
+    /// cell and `shifted_row(y, 1)` the right one.
+    pub fn shifted_row(&self, y: usize, dx: isize) -> Vec<u64> {
+        let row = self.row_words(y);
+        let word_shift = dx.div_euclid(BITS as isize);
+        let bit_shift = dx.rem_euclid(BITS as isize) as u32;
+        let word = |i: isize| -> u64 {
+            if i < 0 || i as usize >= row.len() {
+                0
+            } else {
+                row[i as usize]
+            }
+        };
+
+        let mut out: Vec<u64> = (0..row.len() as isize)
+            .map(|i| {
+                let lo = word(i + word_shift) >> bit_shift;
+                let hi = match bit_shift {
+                    0 => 0,
+                    s => word(i + word_shift + 1) << (BITS as u32 - s),
+                };
+                lo | hi
+            })
+            .collect();
+        if let Some(last) = out.last_mut() {
+            *last &= self.last_word_mask();
+        }
+        out
+    }
+
+    /// returns how many of the eight neighbours of `x, y` are set.
+    pub fn count_neighbours(&self, x: usize, y: usize) -> usize {
+        let mut count = 0;
+        for ny in y.saturating_sub(1)..=(y + 1).min(self.len_y - 1) {
+            for nx in x.saturating_sub(1)..=(x + 1).min(self.len_x - 1) {
+                if (nx, ny) != (x, y) && self.get(nx, ny) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    //--------------------------------------------------------------------
+    // Private
+    //--------------------------------------------------------------------
+
+    /// returns the word index and the bit offset of the cell `x, y`.
+    fn index(&self, x: usize, y: usize) -> (usize, usize) {
+        assert!(x < self.len_x && y < self.len_y, "Index out of bounds.");
+        (y * self.words_per_row + x / BITS, x % BITS)
+    }
+
+    /// mask of the valid bits in the last word of a row.
+    fn last_word_mask(&self) -> u64 {
+        match self.len_x % BITS {
+            0 => !0,
+            r => (1 << r) - 1,
+        }
+    }
+
+    /// clears the padding bits of row `y`.
+    fn mask_row(&mut self, y: usize) {
+        if self.words_per_row > 0 {
+            let mask = self.last_word_mask();
+            self.words[(y + 1) * self.words_per_row - 1] &= mask;
+        }
+    }
+}
+
+impl From<&Grid<bool>> for BitGrid {
+    fn from(grid: &Grid<bool>) -> Self {
+        let mut out = BitGrid::new(grid.len_x, grid.len_y, false);
+        for y in 0..grid.len_y {
+            for x in 0..grid.len_x {
+                out.set(x, y, *grid.get(x, y));
+            }
+        }
+        out
+    }
+}
+
+impl From<&BitGrid> for Grid<bool> {
+    fn from(grid: &BitGrid) -> Self {
+        let flat = (0..grid.len_y).flat_map(|y| grid.row(y)).collect();
+        Grid::new_with_vec(grid.len_x, grid.len_y, flat)
+    }
+}
+
+//--------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_set() {
+        let mut grid = BitGrid::new(130, 2, true);
+        assert_eq!(grid.count(), 260);
+        grid.set(129, 1, false);
+        grid.set(0, 0, false);
+        assert!(!grid.get(129, 1));
+        assert!(!grid.wrap(130, 0));
+        assert!(grid.wrap_x(131, 0));
+        assert!(!grid.wrap_y(129, 3));
+        assert_eq!(grid.count_row(0), 129);
+        assert_eq!(grid.count_row(1), 129);
+        assert_eq!(grid.count_neighbours(1, 0), 4);
+    }
+
+    #[test]
+    fn test_shifted_row() {
+        let mut grid = BitGrid::new(70, 1, false);
+        grid.set(0, 0, true);
+        grid.set(63, 0, true);
+        grid.set(69, 0, true);
+
+        let right = grid.shifted_row(0, 1); // bit x holds x + 1
+        assert_eq!(right, vec![1 << 62, 1 << 4]);
+        let left = grid.shifted_row(0, -1); // bit x holds x - 1
+        assert_eq!(left, vec![1 << 1, 1]);
+        let far = grid.shifted_row(0, -64);
+        assert_eq!(far, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_grid_round_trip() {
+        let flat = vec![true, false, false, false, true, true];
+        let grid = Grid::new_with_vec(3, 2, flat.clone());
+        let bits = BitGrid::from(&grid);
+        assert_eq!(bits, BitGrid::new_with_vec(3, 2, flat));
+        assert_eq!(Grid::from(&bits), grid);
+    }
+}
@@ -0,0 +1,140 @@
+//! Compass directions for walks over a grid.
+//!
+//! Offsets follow the `Grid` convention: `x` grows to the east and `y` grows
+//! to the south, so `North` is `(0, -1)`.
+
+use std::{convert::TryFrom, error::Error, fmt};
+
+use super::base2d::Base2d;
+use Direction::*;
+
+/// One of the eight compass directions, listed clockwise from `North`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction {
+    /// The four cardinal directions, clockwise from `North`.
+    pub const CARDINAL: [Direction; 4] = [North, East, South, West];
+
+    /// All eight directions, clockwise from `North`.
+    pub const ALL: [Direction; 8] = [
+        North, NorthEast, East, SouthEast, South, SouthWest, West, NorthWest,
+    ];
+
+    /// returns the direction 90 degrees counterclockwise.
+    pub fn turn_left(self) -> Direction {
+        self.turn(-90)
+    }
+
+    /// returns the direction 90 degrees clockwise.
+    pub fn turn_right(self) -> Direction {
+        self.turn(90)
+    }
+
+    /// returns the direction after turning by `degrees`, clockwise if positive
+    /// and counterclockwise if negative.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `degrees` is not a multiple of 45.
+    pub fn turn(self, degrees: i32) -> Direction {
+        assert!(degrees % 45 == 0, "Turn must be a multiple of 45 degrees.");
+        let idx = (self as i32 + degrees / 45).rem_euclid(8);
+        Direction::ALL[idx as usize]
+    }
+
+    /// returns the unit step taken when moving in this direction.
+    pub fn to_offset(self) -> Base2d<i64> {
+        let (x, y) = match self {
+            North => (0, -1),
+            NorthEast => (1, -1),
+            East => (1, 0),
+            SouthEast => (1, 1),
+            South => (0, 1),
+            SouthWest => (-1, 1),
+            West => (-1, 0),
+            NorthWest => (-1, -1),
+        };
+        Base2d::new(x, y)
+    }
+}
+
+/// Parses the initials `N`, `E`, `S` and `W` of the cardinal directions.
+impl TryFrom<char> for Direction {
+    type Error = ParseDirectionError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'N' => Ok(North),
+            'E' => Ok(East),
+            'S' => Ok(South),
+            'W' => Ok(West),
+            _ => Err(ParseDirectionError(c)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseDirectionError(char);
+
+impl Error for ParseDirectionError {}
+
+impl fmt::Display for ParseDirectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid direction '{}'.", self.0)
+    }
+}
+
+//--------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turns() {
+        assert_eq!(North.turn_right(), East);
+        assert_eq!(North.turn_left(), West);
+        assert_eq!(NorthWest.turn_right(), NorthEast);
+        assert_eq!(East.turn(270), North);
+        assert_eq!(East.turn(-45), NorthEast);
+        assert_eq!(South.turn(720), South);
+    }
+
+    #[test]
+    fn test_offsets() {
+        for &dir in Direction::ALL.iter() {
+            assert_eq!(dir.turn_right().to_offset(), dir.to_offset().rotate_right());
+        }
+        let sum = Direction::ALL
+            .iter()
+            .fold(Base2d::new(0, 0), |acc, d| acc + d.to_offset());
+        assert_eq!(sum, Base2d::new(0, 0));
+    }
+
+    #[test]
+    fn test_walk() {
+        // F10 N3 F7 R90 F11 from the ship navigation example
+        let mut pos = Base2d::new(0i64, 0);
+        let mut heading = Direction::try_from('E').unwrap();
+        pos += heading.to_offset() * 10;
+        pos += North.to_offset() * 3;
+        pos += heading.to_offset() * 7;
+        heading = heading.turn_right();
+        pos += heading.to_offset() * 11;
+        assert_eq!(pos, Base2d::new(17, 8));
+        assert_eq!(pos.manhattan(Base2d::new(0, 0)), 25);
+        assert!(Direction::try_from('X').is_err());
+    }
+}
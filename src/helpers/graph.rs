@@ -1,108 +1,523 @@
 //! A graph data structure backed by a Vec and a Hashmap. The Vec stores the
 //! vertices whereas the Hashmap provides a quick way to find a vertex by its
 //! name.
+//!
+//! `DiGraph` is generic over the names of the vertices and the weights of the
+//! edges, `Graph` is the string named, `u32` weighted, graph used by the bag
+//! rules. Names are stored owned and taken and returned borrowed, so a `Graph`
+//! stores `String`s but works with `&str`.
+//!
+//! ```
+//! use adv20::helpers::graph::DiGraph;
+//! // adapters linked to the ones they can be plugged into, unweighted
+//! let mut graph = DiGraph::<u8, ()>::new();
+//! graph.add_edge(&0, &1, ());
+//! graph.add_edge(&1, &4, ());
+//! assert_eq!(graph.successors(&1).collect::<Vec<_>>(), [(&4, ())]);
+//! ```
 
 // todo:
-// - implement tests
 // - test performance for this implementation agains one soleny based on HashMap
 
-use std::collections::{HashMap, HashSet};
+use std::{
+    any,
+    borrow::Borrow,
+    collections::{HashMap, HashSet, VecDeque},
+    convert::TryFrom,
+    error::Error,
+    fmt,
+    hash::Hash,
+};
 
-#[derive(Default)]
-pub struct Graph {
-    names: HashMap<String, usize>,
-    vertices: Vec<Vertex>,
+pub mod components;
+pub mod dot;
+pub mod io;
+pub mod paths;
+
+pub struct DiGraph<N: ToOwned + ?Sized, W> {
+    names: HashMap<N::Owned, usize>,
+    vertices: Vec<Vertex<N::Owned, W>>,
+}
+
+/// A graph of named vertices with `u32` weights.
+pub type Graph = DiGraph<str, u32>;
+
+/// Types vertices can be named by, e.g. `str` or `u8`. Names are stored as
+/// `Self::Owned`.
+pub trait Name: Hash + Eq + ToOwned<Owned: Hash + Eq> {}
+
+impl<N> Name for N where N: Hash + Eq + ToOwned<Owned: Hash + Eq> + ?Sized {}
+
+/// Edge weights as written by `to_dot`, `to_edge_list` and `to_json`, and
+/// read back by `from_edge_list` and `from_json`. The unit weights of
+/// unweighted graphs have no label and are left out.
+pub trait EdgeLabel: Sized {
+    fn label(&self) -> Option<String>;
+
+    /// parses a weight from its label, `None` for an edge saved without one.
+    fn from_label(label: Option<&str>) -> Option<Self>;
+}
+
+impl EdgeLabel for () {
+    fn label(&self) -> Option<String> {
+        None
+    }
+
+    fn from_label(label: Option<&str>) -> Option<Self> {
+        label.is_none().then_some(())
+    }
 }
 
-struct Vertex {
-    name: String,
-    pointed_by: Vec<Edge>,
-    points_to: Vec<Edge>,
+macro_rules! edge_label {
+    ($($t:ty),*) => {$(
+        impl EdgeLabel for $t {
+            fn label(&self) -> Option<String> {
+                Some(self.to_string())
+            }
+
+            fn from_label(label: Option<&str>) -> Option<Self> {
+                label?.parse().ok()
+            }
+        }
+    )*};
+}
+
+edge_label!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+struct Vertex<N, W> {
+    name: N,
+    pointed_by: Vec<Edge<W>>,
+    points_to: Vec<Edge<W>>,
 }
 
-struct Edge {
+struct Edge<W> {
     to_vertex: usize,
-    weight: u32,
+    weight: W,
+}
+
+/// Which edges a traversal follows.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EdgeDirection {
+    /// from a vertex to the vertices it points to.
+    Outgoing,
+    /// from a vertex to the vertices pointing to it.
+    Incoming,
+}
+
+/// Depth first iterator over the names of the vertices reachable from a
+/// starting vertex, the starting vertex included. Created by `DiGraph::dfs`.
+pub struct Dfs<'a, N: ToOwned + ?Sized, W> {
+    graph: &'a DiGraph<N, W>,
+    direction: EdgeDirection,
+    stack: Vec<usize>,
+    visited: Vec<bool>,
+}
+
+/// Breadth first iterator over the names of the vertices reachable from a
+/// starting vertex, the starting vertex included. Created by `DiGraph::bfs`.
+pub struct Bfs<'a, N: ToOwned + ?Sized, W> {
+    graph: &'a DiGraph<N, W>,
+    direction: EdgeDirection,
+    queue: VecDeque<usize>,
+    visited: Vec<bool>,
 }
 
 //--------------------------------------------------------------------
 // Implementation
 //--------------------------------------------------------------------
 
-impl Graph {
+impl<N: ToOwned + ?Sized, W> Default for DiGraph<N, W> {
+    fn default() -> Self {
+        DiGraph {
+            names: HashMap::new(),
+            vertices: Vec::new(),
+        }
+    }
+}
+
+/// Shows every vertex with its outgoing edges, as `to, weight` pairs.
+impl<N: ToOwned + ?Sized, W> DiGraph<N, W> {
+    /// returns the name of the vertex `idx`.
+    fn name(&self, idx: usize) -> &N {
+        self.vertices[idx].name.borrow()
+    }
+}
+
+impl<N, W> DiGraph<N, W>
+where
+    N: Name + ?Sized,
+    W: Copy,
+{
     pub fn new() -> Self {
-        Graph::default()
+        DiGraph::default()
     }
 
-    pub fn add_edge(&mut self, vertex0: &str, vertex1: &str, weight: u32) {
+    pub fn add_edge(&mut self, vertex0: &N, vertex1: &N, weight: W) {
         let v0 = self.get_or_add_vertex(vertex0);
         let v1 = self.get_or_add_vertex(vertex1);
         self.vertices[v0].points_to.push(Edge::new(v1, weight));
         self.vertices[v1].pointed_by.push(Edge::new(v0, weight));
     }
 
-    pub fn add_edges(&mut self, vertex0: &str, vertices: &[(u32, String)]) {
+    pub fn add_edges(&mut self, vertex0: &N, vertices: &[(W, N::Owned)]) {
         for vertex in vertices {
-            self.add_edge(vertex0, &vertex.1, vertex.0);
+            self.add_edge(vertex0, vertex.1.borrow(), vertex.0);
         }
     }
 
+    /// Adds a vertex without any edge, unless there already is one named
+    /// `name`, and returns its index.
+    pub fn insert_vertex(&mut self, name: &N) -> usize {
+        self.get_or_add_vertex(name)
+    }
+
+    //------------------------------
+    // Changes
+    //------------------------------
+
+    /// Removes the first edge from `vertex0` to `vertex1` and returns its
+    /// weight, or `None` if there is no such edge. Vertices are kept.
+    pub fn remove_edge(&mut self, vertex0: &N, vertex1: &N) -> Option<W> {
+        let (v0, v1) = (self.vertex(vertex0)?, self.vertex(vertex1)?);
+        let out = self.vertices[v0]
+            .points_to
+            .iter()
+            .position(|e| e.to_vertex == v1)?;
+        let weight = self.vertices[v0].points_to.remove(out).weight;
+        let inc = self.vertices[v1]
+            .pointed_by
+            .iter()
+            .position(|e| e.to_vertex == v0)
+            .unwrap();
+        self.vertices[v1].pointed_by.remove(inc);
+        Some(weight)
+    }
+
+    /// Changes the weight of the first edge from `vertex0` to `vertex1` and
+    /// returns the previous one, or `None` if there is no such edge.
+    pub fn set_weight(&mut self, vertex0: &N, vertex1: &N, weight: W) -> Option<W> {
+        let (v0, v1) = (self.vertex(vertex0)?, self.vertex(vertex1)?);
+        let out = self.vertices[v0]
+            .points_to
+            .iter_mut()
+            .find(|e| e.to_vertex == v1)?;
+        let previous = std::mem::replace(&mut out.weight, weight);
+        let inc = self.vertices[v1]
+            .pointed_by
+            .iter_mut()
+            .find(|e| e.to_vertex == v0)
+            .unwrap();
+        inc.weight = weight;
+        Some(previous)
+    }
+
+    /// Removes the vertex `name` with all its edges and returns `true`, or
+    /// `false` if there is no such vertex.
+    ///
+    /// The last vertex is moved to the index of the removed one, so indexes
+    /// taken before the removal must not be used afterwards.
+    pub fn remove_vertex(&mut self, name: &N) -> bool {
+        let idx = match self.names.remove(name) {
+            Some(idx) => idx,
+            None => return false,
+        };
+        let removed = self.vertices.swap_remove(idx);
+        // the vertex moved from the end, if any, now lives at `idx`
+        let last = self.vertices.len();
+        let at = |n: usize| if n == last { idx } else { n };
+
+        // unlink the removed vertex from its neighbours
+        for e in removed.points_to.iter().filter(|e| e.to_vertex != idx) {
+            let v = &mut self.vertices[at(e.to_vertex)];
+            v.pointed_by.retain(|e| e.to_vertex != idx);
+        }
+        for e in removed.pointed_by.iter().filter(|e| e.to_vertex != idx) {
+            let v = &mut self.vertices[at(e.to_vertex)];
+            v.points_to.retain(|e| e.to_vertex != idx);
+        }
+        if idx == last {
+            return true;
+        }
+
+        // renumber the moved vertex, in its own edges and in the edges of its
+        // neighbours
+        self.names.insert(self.name(idx).to_owned(), idx);
+        let moved = &self.vertices[idx];
+        let mut neighbours: Vec<usize> = moved
+            .points_to
+            .iter()
+            .chain(&moved.pointed_by)
+            .map(|e| at(e.to_vertex))
+            .collect();
+        neighbours.push(idx);
+        neighbours.sort_unstable();
+        neighbours.dedup();
+        for n in neighbours {
+            let v = &mut self.vertices[n];
+            for e in v.points_to.iter_mut().chain(v.pointed_by.iter_mut()) {
+                e.to_vertex = at(e.to_vertex);
+            }
+        }
+        true
+    }
+
+    /// Adds the vertices and edges of `other` to the graph. An edge joining
+    /// two vertices that are already joined changes the weight of the
+    /// existing edge instead of being added twice. Parallel edges are matched
+    /// in order: the second edge `a -> b` of `other` changes the second edge
+    /// `a -> b` of the graph, and is added if there is none.
+    pub fn merge(&mut self, other: &DiGraph<N, W>) {
+        let at: Vec<usize> = other
+            .vertices
+            .iter()
+            .map(|v| self.get_or_add_vertex(v.name.borrow()))
+            .collect();
+        for (from, v) in other.vertices.iter().enumerate() {
+            let mut seen = HashMap::<usize, usize>::new();
+            for edge in &v.points_to {
+                let nth = seen.entry(edge.to_vertex).or_insert(0);
+                self.set_or_add_edge(at[from], at[edge.to_vertex], *nth, edge.weight);
+                *nth += 1;
+            }
+        }
+    }
+
+    //------------------------------
+    // Queries
+    //------------------------------
+
+    /// Returns the index of the vertex named `name`. Indexes are stable as
+    /// long as no vertex is removed, and are the ones returned by
+    /// `list_ancestors`.
+    pub fn vertex(&self, name: &N) -> Option<usize> {
+        self.names.get(name).copied()
+    }
+
+    /// Returns the name of the vertex at index `idx`.
+    pub fn vertex_name(&self, idx: usize) -> Option<&N> {
+        (idx < self.vertices.len()).then(|| self.name(idx))
+    }
+
+    pub fn contains(&self, name: &N) -> bool {
+        self.names.contains_key(name)
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.vertices.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.vertices.iter().map(|v| v.points_to.len()).sum()
+    }
+
+    /// Returns the vertices `name` points to, with the weights of the edges.
+    /// Empty if there is no such vertex.
+    pub fn successors(&self, name: &N) -> impl Iterator<Item = (&N, W)> + '_ {
+        self.neighbours(self.vertex(name), EdgeDirection::Outgoing)
+    }
+
+    /// Returns the vertices pointing to `name`, with the weights of the edges.
+    /// Empty if there is no such vertex.
+    pub fn predecessors(&self, name: &N) -> impl Iterator<Item = (&N, W)> + '_ {
+        self.neighbours(self.vertex(name), EdgeDirection::Incoming)
+    }
+
+    /// Returns every edge as `(from, to, weight)`, grouped by `from` in the
+    /// order vertices were added.
+    pub fn edges(&self) -> impl Iterator<Item = (&N, &N, W)> + '_ {
+        self.vertices.iter().flat_map(move |v| {
+            v.points_to
+                .iter()
+                .map(move |e| (v.name.borrow(), self.name(e.to_vertex), e.weight))
+        })
+    }
+
+    //------------------------------
+    // Algorithms
+    //------------------------------
+
     /// Returns a set containing the indexes of all ancestor vertices
-    pub fn list_ancestors(&self, name: &str) -> HashSet<usize> {
-        let mut cache = HashSet::<usize>::new();
-        if let Some(&idx) = self.names.get(name) {
-            self.rec_list_ancestors(&mut cache, idx);
+    pub fn list_ancestors(&self, name: &N) -> HashSet<usize> {
+        self.vertex(name)
+            .map(|idx| self.reachable(idx, EdgeDirection::Incoming))
+            .unwrap_or_default()
+    }
+
+    /// Returns a set containing the indexes of all descendant vertices
+    pub fn descendants(&self, name: &N) -> HashSet<usize> {
+        self.vertex(name)
+            .map(|idx| self.reachable(idx, EdgeDirection::Outgoing))
+            .unwrap_or_default()
+    }
+
+    /// Returns the names of all vertices ordered so that every edge goes from
+    /// a vertex to a later one, or the first cycle found.
+    pub fn topological_order(&self) -> Result<Vec<&N>, Cycle<N::Owned>> {
+        let mut order = self.post_order(0..self.vertices.len())?;
+        order.reverse();
+        Ok(order.into_iter().map(|idx| self.name(idx)).collect())
+    }
+
+    /// Returns a depth first iterator over the vertices reachable from `name`,
+    /// following edges in `direction`. Empty if there is no such vertex.
+    pub fn dfs(&self, name: &N, direction: EdgeDirection) -> Dfs<'_, N, W> {
+        Dfs {
+            graph: self,
+            direction,
+            stack: self.vertex(name).into_iter().collect(),
+            visited: vec![false; self.vertices.len()],
         }
-        cache
     }
 
-    /// Returns the combined weight of all successors vertices
-    /// For example, the graph 1 - 2 - 3
-    ///                             |- 4
-    /// has a combined weight of 1 + 1 * ( 2 + 2 * (4 + 3)) = 17.
-    pub fn weigh_successors(&self, name: &str) -> u32 {
-        let mut weight = 0;
-        if let Some(&idx) = self.names.get(name) {
-            weight = self.rec_weigh_sucessors(idx);
+    /// Returns a breadth first iterator over the vertices reachable from
+    /// `name`, following edges in `direction`. Empty if there is no such
+    /// vertex.
+    pub fn bfs(&self, name: &N, direction: EdgeDirection) -> Bfs<'_, N, W> {
+        let mut visited = vec![false; self.vertices.len()];
+        let queue: VecDeque<usize> = self.vertex(name).into_iter().collect();
+        queue.iter().for_each(|&idx| visited[idx] = true);
+        Bfs {
+            graph: self,
+            direction,
+            queue,
+            visited,
         }
-        weight
     }
 
     //------------------------------
     // Helpers
     //------------------------------
 
-    fn rec_list_ancestors(&self, cache: &mut HashSet<usize>, idx: usize) {
-        let pointed_by = &self.vertices[idx].pointed_by;
-        for edge in pointed_by {
-            if !cache.contains(&edge.to_vertex) {
-                cache.insert(edge.to_vertex);
-                self.rec_list_ancestors(cache, edge.to_vertex);
+    /// returns the indexes of the vertices reachable from `idx` following
+    /// edges in `direction`. `idx` is only included if it is on a cycle.
+    fn reachable(&self, idx: usize, direction: EdgeDirection) -> HashSet<usize> {
+        let mut cache = HashSet::<usize>::new();
+        let mut stack = vec![idx];
+        while let Some(idx) = stack.pop() {
+            for edge in self.edges_of(idx, direction) {
+                if cache.insert(edge.to_vertex) {
+                    stack.push(edge.to_vertex);
+                }
+            }
+        }
+        cache
+    }
+
+    /// returns the vertices reachable from `roots` in depth first post-order,
+    /// so every vertex comes after its successors, or the first cycle found.
+    fn post_order<I>(&self, roots: I) -> Result<Vec<usize>, Cycle<N::Owned>>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        #[derive(Copy, Clone, PartialEq)]
+        enum State {
+            New,
+            Open,
+            Done,
+        }
+
+        let mut state = vec![State::New; self.vertices.len()];
+        let mut order = Vec::new();
+        for root in roots {
+            if state[root] != State::New {
+                continue;
+            }
+            state[root] = State::Open;
+            // the open vertices, each with the position of its next edge
+            let mut path = vec![(root, 0)];
+            while let Some(&(idx, next)) = path.last() {
+                match self.vertices[idx].points_to.get(next) {
+                    Some(edge) => {
+                        path.last_mut().unwrap().1 += 1;
+                        let to = edge.to_vertex;
+                        match state[to] {
+                            State::New => {
+                                state[to] = State::Open;
+                                path.push((to, 0));
+                            }
+                            State::Open => {
+                                let start = path.iter().position(|&(i, _)| i == to).unwrap();
+                                let names =
+                                    path[start..].iter().map(|&(i, _)| self.name(i).to_owned());
+                                return Err(Cycle {
+                                    vertices: names.collect(),
+                                });
+                            }
+                            State::Done => {}
+                        }
+                    }
+                    None => {
+                        state[idx] = State::Done;
+                        order.push(idx);
+                        path.pop();
+                    }
+                }
             }
         }
+        Ok(order)
+    }
+
+    /// returns the neighbours of the vertex `idx`, if any, in `direction`,
+    /// with the edge weights.
+    fn neighbours(
+        &self,
+        idx: Option<usize>,
+        direction: EdgeDirection,
+    ) -> impl Iterator<Item = (&N, W)> + '_ {
+        let edges = match idx {
+            Some(idx) => self.edges_of(idx, direction),
+            None => &[],
+        };
+        edges
+            .iter()
+            .map(move |e| (self.name(e.to_vertex), e.weight))
     }
 
-    fn rec_weigh_sucessors(&self, idx: usize) -> u32 {
-        let points_to = &self.vertices[idx].points_to;
-        let mut weight = 0;
-        for edge in points_to {
-            weight += edge.weight + edge.weight * self.rec_weigh_sucessors(edge.to_vertex);
+    /// returns the edges leaving `idx` in `direction`.
+    fn edges_of(&self, idx: usize, direction: EdgeDirection) -> &[Edge<W>] {
+        match direction {
+            EdgeDirection::Outgoing => &self.vertices[idx].points_to,
+            EdgeDirection::Incoming => &self.vertices[idx].pointed_by,
         }
-        weight
     }
 
-    fn add_vertex(&mut self, v: Vertex) -> usize {
+    fn add_vertex(&mut self, v: Vertex<N::Owned, W>) -> usize {
         let idx = self.vertices.len();
         self.vertices.push(v);
-        self.names
-            .insert(self.vertices.last().unwrap().name.clone(), idx);
+        self.names.insert(self.name(idx).to_owned(), idx);
         idx
     }
 
-    fn get_or_add_vertex(&mut self, vertex: &str) -> usize {
+    /// sets the weight of the `nth` edge from `v0` to `v1`, or adds an edge if
+    /// there are not that many.
+    fn set_or_add_edge(&mut self, v0: usize, v1: usize, nth: usize, weight: W) {
+        let out = self.vertices[v0]
+            .points_to
+            .iter_mut()
+            .filter(|e| e.to_vertex == v1)
+            .nth(nth);
+        match out {
+            Some(out) => {
+                out.weight = weight;
+                let inc = self.vertices[v1]
+                    .pointed_by
+                    .iter_mut()
+                    .filter(|e| e.to_vertex == v0)
+                    .nth(nth)
+                    .unwrap();
+                inc.weight = weight;
+            }
+            None => {
+                self.vertices[v0].points_to.push(Edge::new(v1, weight));
+                self.vertices[v1].pointed_by.push(Edge::new(v0, weight));
+            }
+        }
+    }
+
+    fn get_or_add_vertex(&mut self, vertex: &N) -> usize {
         let idx;
         if !self.names.contains_key(vertex) {
-            idx = self.add_vertex(Vertex::new(vertex));
+            idx = self.add_vertex(Vertex::new(vertex.to_owned()));
         } else {
             idx = *self.names.get(vertex).unwrap();
         }
@@ -110,24 +525,423 @@ impl Graph {
     }
 }
 
-impl Vertex {
-    fn new(name: &str) -> Self {
+//------------------------------
+// Weighing
+//------------------------------
+
+impl<N, W> DiGraph<N, W>
+where
+    N: Name + ?Sized,
+    W: Copy + Into<u64> + TryFrom<u64>,
+{
+    /// Returns the combined weight of all successors vertices
+    /// For example, the graph 1 - 2 - 3
+    ///                             |- 4
+    /// has a combined weight of 1 + 1 * ( 2 + 2 * (4 + 3)) = 17.
+    ///
+    /// Fails if a cycle can be reached from `name`, as its weight would be
+    /// infinite.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the weight does not fit in a `W`, see
+    /// `weigh_successors_checked`.
+    pub fn weigh_successors(&self, name: &N) -> Result<W, Cycle<N::Owned>> {
+        let weight = self.weigh_successors_checked(name)?;
+        Ok(weight
+            .and_then(|w| W::try_from(w).ok())
+            .unwrap_or_else(|| panic!("Combined weight overflows {}.", any::type_name::<W>())))
+    }
+
+    /// Same as `weigh_successors`, computed with `u64` arithmetic. Returns
+    /// `None` if the weight overflows.
+    pub fn weigh_successors_checked(&self, name: &N) -> Result<Option<u64>, Cycle<N::Owned>> {
+        match self.vertex(name) {
+            Some(idx) => self.weigh(idx),
+            None => Ok(Some(0)),
+        }
+    }
+
+    /// returns the combined weight of the successors of `start`. The weight
+    /// of every vertex is computed once, in post-order, so shared sub-graphs
+    /// are not weighed again.
+    fn weigh(&self, start: usize) -> Result<Option<u64>, Cycle<N::Owned>> {
+        let mut cache = vec![0u64; self.vertices.len()];
+        for idx in self.post_order(Some(start))? {
+            let mut weight = 0u64;
+            for edge in &self.vertices[idx].points_to {
+                let w: u64 = edge.weight.into();
+                let inner = w.checked_mul(cache[edge.to_vertex]);
+                match inner.and_then(|i| weight.checked_add(w)?.checked_add(i)) {
+                    Some(sum) => weight = sum,
+                    None => return Ok(None),
+                }
+            }
+            cache[idx] = weight;
+        }
+        Ok(Some(cache[start]))
+    }
+}
+
+impl<N, W> Vertex<N, W> {
+    fn new(name: N) -> Self {
         Self {
-            name: name.to_owned(),
+            name,
             pointed_by: Vec::new(),
             points_to: Vec::new(),
         }
     }
 }
 
-impl Edge {
-    fn new(to_vertex: usize, weight: u32) -> Self {
+impl<W> Edge<W> {
+    fn new(to_vertex: usize, weight: W) -> Self {
         Self { to_vertex, weight }
     }
 }
+
+impl<'a, N, W> Iterator for Dfs<'a, N, W>
+where
+    N: Name + ?Sized,
+    W: Copy,
+{
+    type Item = &'a N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(idx) = self.stack.pop() {
+            if self.visited[idx] {
+                continue;
+            }
+            self.visited[idx] = true;
+            // reversed, so the first edge is the first one to be explored
+            for edge in self.graph.edges_of(idx, self.direction).iter().rev() {
+                if !self.visited[edge.to_vertex] {
+                    self.stack.push(edge.to_vertex);
+                }
+            }
+            return Some(self.graph.name(idx));
+        }
+        None
+    }
+}
+
+impl<'a, N, W> Iterator for Bfs<'a, N, W>
+where
+    N: Name + ?Sized,
+    W: Copy,
+{
+    type Item = &'a N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.queue.pop_front()?;
+        for edge in self.graph.edges_of(idx, self.direction) {
+            if !self.visited[edge.to_vertex] {
+                self.visited[edge.to_vertex] = true;
+                self.queue.push_back(edge.to_vertex);
+            }
+        }
+        Some(self.graph.name(idx))
+    }
+}
+
+//--------------------------------------------------------------------
+// Errors
+//--------------------------------------------------------------------
+
+/// Error listing the vertices of a cycle, in the order of its edges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<N = String> {
+    pub vertices: Vec<N>,
+}
+
+impl<N: fmt::Debug + fmt::Display> Error for Cycle<N> {}
+
+impl<N: fmt::Display> fmt::Display for Cycle<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Cycle found between vertices: ")?;
+        for v in &self.vertices {
+            write!(f, "{} -> ", v)?;
+        }
+        write!(f, "{}.", self.vertices[0])
+    }
+}
+
 //--------------------------------------------------------------------
 // Tests
 //--------------------------------------------------------------------
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+
+    /// a - b - d
+    ///   \ c /
+    ///
+    /// Shared with the tests of the submodules.
+    pub(super) fn diamond() -> Graph {
+        let mut graph = Graph::new();
+        graph.add_edge("a", "b", 2);
+        graph.add_edge("a", "c", 3);
+        graph.add_edge("b", "d", 4);
+        graph.add_edge("c", "d", 5);
+        graph
+    }
+
+    /// a chain `0 -> 1 -> ... -> len - 1` with unit weights.
+    fn chain(len: usize) -> Graph {
+        let mut graph = Graph::new();
+        for i in 1..len {
+            graph.add_edge(&(i - 1).to_string(), &i.to_string(), 1);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_queries() {
+        let graph = diamond();
+        assert_eq!((graph.vertex_count(), graph.edge_count()), (4, 4));
+        assert!(graph.contains("c") && !graph.contains("z"));
+        let d = graph.vertex("d").unwrap();
+        assert_eq!(graph.vertex_name(d).unwrap(), "d");
+        assert_eq!(graph.vertex_name(4), None);
+        let mut names: Vec<&str> = graph
+            .list_ancestors("d")
+            .into_iter()
+            .filter_map(|idx| graph.vertex_name(idx))
+            .collect();
+        names.sort_unstable();
+        assert_eq!(names, ["a", "b", "c"]);
+
+        let succ: Vec<_> = graph.successors("a").collect();
+        assert_eq!(succ, [("b", 2), ("c", 3)]);
+        let pred: Vec<_> = graph.predecessors("d").collect();
+        assert_eq!(pred, [("b", 4), ("c", 5)]);
+        assert_eq!(graph.successors("z").count(), 0);
+        let edges: Vec<_> = graph.edges().collect();
+        assert_eq!(
+            edges,
+            [("a", "b", 2), ("a", "c", 3), ("b", "d", 4), ("c", "d", 5)]
+        );
+    }
+
+    #[test]
+    fn test_edge_changes() {
+        let mut graph = diamond();
+        assert_eq!(graph.set_weight("a", "b", 7), Some(2));
+        assert_eq!(graph.predecessors("b").next(), Some(("a", 7)));
+        assert_eq!(graph.remove_edge("a", "b"), Some(7));
+        assert_eq!(graph.remove_edge("a", "b"), None);
+        assert_eq!(graph.set_weight("a", "z", 1), None);
+        assert_eq!((graph.vertex_count(), graph.edge_count()), (4, 3));
+        assert_eq!(graph.list_ancestors("b").len(), 0);
+        assert_eq!(graph.weigh_successors("a").unwrap(), 3 + 3 * 5);
+    }
+
+    #[test]
+    fn test_remove_vertex() {
+        let mut graph = diamond();
+        graph.add_edge("d", "d", 1);
+        graph.add_edge("d", "a", 1);
+        assert!(graph.remove_vertex("b"));
+        assert!(!graph.remove_vertex("b"));
+        // "d" was moved to the index of "b"
+        assert_eq!(graph.vertex("d"), Some(1));
+        assert_eq!(graph.vertex_name(1).unwrap(), "d");
+        assert_eq!(graph.vertex_count(), 3);
+        let mut edges: Vec<_> = graph.edges().collect();
+        edges.sort_unstable();
+        assert_eq!(
+            edges,
+            [("a", "c", 3), ("c", "d", 5), ("d", "a", 1), ("d", "d", 1)]
+        );
+        let pred: Vec<_> = graph.predecessors("d").map(|(v, _)| v).collect();
+        assert_eq!(pred, ["c", "d"]);
+        assert_eq!(graph.list_ancestors("a").len(), 3);
+
+        // removing the last vertex moves nothing
+        assert!(graph.remove_vertex("c"));
+        assert_eq!(graph.vertex("d"), Some(1));
+        assert_eq!(graph.edges().count(), 2);
+        assert!(graph.remove_vertex("a"));
+        assert_eq!(graph.successors("d").collect::<Vec<_>>().len(), 1);
+        assert!(graph.remove_vertex("d"));
+        assert_eq!((graph.vertex_count(), graph.edge_count()), (0, 0));
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut graph = diamond();
+        let mut other = Graph::new();
+        other.add_edge("a", "b", 9);
+        other.add_edge("d", "e", 1);
+        other.insert_vertex("f");
+        graph.merge(&other);
+        assert_eq!((graph.vertex_count(), graph.edge_count()), (6, 5));
+        assert_eq!(graph.successors("a").next(), Some(("b", 9)));
+        assert_eq!(graph.descendants("a").len(), 4);
+        assert!(graph.contains("f"));
+    }
+
+    #[test]
+    fn test_merge_parallel_edges() {
+        let mut graph = diamond();
+        let mut other = Graph::new();
+        other.add_edge("a", "b", 7);
+        other.add_edge("a", "b", 8);
+        other.add_edge("c", "d", 1);
+        graph.merge(&other);
+        assert_eq!(graph.edge_count(), 5);
+        let successors: Vec<_> = graph.successors("a").collect();
+        assert_eq!(successors, [("b", 7), ("c", 3), ("b", 8)]);
+        assert_eq!(graph.successors("c").collect::<Vec<_>>(), [("d", 1)]);
+        assert_eq!(
+            graph.predecessors("b").collect::<Vec<_>>(),
+            [("a", 7), ("a", 8)]
+        );
+
+        // merging again matches both edges instead of adding a third one
+        other.set_weight("a", "b", 6);
+        graph.merge(&other);
+        let successors: Vec<_> = graph.successors("a").collect();
+        assert_eq!(successors, [("b", 6), ("c", 3), ("b", 8)]);
+    }
+
+    #[test]
+    fn test_list_ancestors() {
+        let graph = diamond();
+        assert_eq!(graph.list_ancestors("d").len(), 3);
+        assert_eq!(graph.list_ancestors("b").len(), 1);
+        assert!(graph.list_ancestors("a").is_empty());
+        assert!(graph.list_ancestors("z").is_empty());
+    }
+
+    #[test]
+    fn test_descendants() {
+        let graph = diamond();
+        assert_eq!(graph.descendants("a").len(), 3);
+        assert_eq!(
+            graph.descendants("c"),
+            graph.vertex("d").into_iter().collect()
+        );
+        assert!(graph.descendants("d").is_empty());
+        assert!(graph.descendants("z").is_empty());
+    }
+
+    #[test]
+    fn test_weigh_successors() {
+        // 2 + 2 * 4 + 3 + 3 * 5
+        assert_eq!(diamond().weigh_successors("a").unwrap(), 28);
+        assert_eq!(diamond().weigh_successors("d").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_deep_graph() {
+        let len = 100_000;
+        let graph = chain(len);
+        assert_eq!(graph.list_ancestors(&(len - 1).to_string()).len(), len - 1);
+        assert_eq!(graph.weigh_successors("0").unwrap(), len as u32 - 1);
+        assert_eq!(graph.dfs("0", EdgeDirection::Outgoing).count(), len);
+        assert_eq!(graph.bfs("0", EdgeDirection::Outgoing).count(), len);
+    }
+
+    /// `depth` diamonds stacked on top of each other, with unit weights. The
+    /// number of paths doubles with every diamond.
+    pub(super) fn ladder(depth: usize) -> Graph {
+        let mut graph = Graph::new();
+        for i in 0..depth {
+            let (top, bottom) = (format!("v{}", i), format!("v{}", i + 1));
+            for side in &["a", "b"] {
+                let mid = format!("{}{}", side, i);
+                graph.add_edge(&top, &mid, 1);
+                graph.add_edge(&mid, &bottom, 1);
+            }
+        }
+        graph
+    }
+
+    #[test]
+    fn test_weigh_shared_successors() {
+        // w(i) = 2 * (1 + 1 + w(i + 1)), so w(0) = 4 * (2^depth - 1)
+        assert_eq!(
+            ladder(20).weigh_successors("v0").unwrap(),
+            4 * ((1 << 20) - 1)
+        );
+        assert_eq!(
+            ladder(40).weigh_successors_checked("v0").unwrap(),
+            Some(4 * ((1 << 40) - 1))
+        );
+        assert_eq!(ladder(70).weigh_successors_checked("v0").unwrap(), None);
+        assert_eq!(ladder(1).weigh_successors_checked("z").unwrap(), Some(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows u32")]
+    fn test_weigh_overflow() {
+        ladder(40).weigh_successors("v0").unwrap();
+    }
+
+    #[test]
+    fn test_topological_order() {
+        let graph = diamond();
+        let order: Vec<&str> = graph.topological_order().unwrap();
+        let pos = |name| order.iter().position(|&v| v == name).unwrap();
+        assert_eq!(order.len(), 4);
+        assert!(pos("a") < pos("b") && pos("a") < pos("c"));
+        assert!(pos("b") < pos("d") && pos("c") < pos("d"));
+        assert_eq!(chain(100_000).topological_order().unwrap()[0], "0");
+    }
+
+    #[test]
+    fn test_cycles() {
+        let mut graph = diamond();
+        graph.add_edge("d", "e", 1);
+        graph.add_edge("e", "b", 1);
+        let cycle = graph.topological_order().unwrap_err();
+        assert_eq!(cycle.vertices, ["b", "d", "e"]);
+        assert_eq!(
+            cycle.to_string(),
+            "Cycle found between vertices: b -> d -> e -> b."
+        );
+        assert_eq!(graph.weigh_successors("a"), Err(cycle));
+        assert_eq!(graph.weigh_successors("c").unwrap_err().vertices.len(), 3);
+
+        let mut graph = Graph::new();
+        graph.add_edge("a", "a", 1);
+        assert_eq!(graph.weigh_successors("a").unwrap_err().vertices, ["a"]);
+    }
+
+    #[test]
+    fn test_generic_keys() {
+        // adapters 0, 1, 4, 5, 6, 7: each one links to the next ones within 3
+        let adapters = [0u8, 1, 4, 5, 6, 7];
+        let mut graph = DiGraph::<u8, u64>::new();
+        for (i, a) in adapters.iter().enumerate() {
+            for b in adapters[i + 1..].iter().take_while(|&&b| b - a <= 3) {
+                graph.add_edge(a, b, 1);
+            }
+        }
+        assert_eq!(graph.vertex_count(), 6);
+        assert_eq!(graph.edge_count(), 8);
+        assert_eq!(graph.topological_order().unwrap()[0], &0);
+        let succ: Vec<_> = graph.successors(&4).collect();
+        assert_eq!(succ, [(&5, 1), (&6, 1), (&7, 1)]);
+        // one per path from 4: 4-5, 4-6, 4-7, 4-5-6, 4-5-7, 4-6-7, 4-5-6-7
+        assert_eq!(graph.weigh_successors(&4).unwrap(), 7u64);
+
+        let mut unweighted = DiGraph::<u8, ()>::new();
+        unweighted.add_edges(&1, &[((), 2), ((), 3)]);
+        assert_eq!(unweighted.dfs(&1, EdgeDirection::Outgoing).count(), 3);
+        assert_eq!(unweighted.predecessors(&3).collect::<Vec<_>>(), [(&1, ())]);
+    }
+
+    #[test]
+    fn test_traversals() {
+        let graph = diamond();
+        let dfs: Vec<&str> = graph.dfs("a", EdgeDirection::Outgoing).collect();
+        assert_eq!(dfs, ["a", "b", "d", "c"]);
+        let bfs: Vec<&str> = graph.bfs("a", EdgeDirection::Outgoing).collect();
+        assert_eq!(bfs, ["a", "b", "c", "d"]);
+        let up: Vec<&str> = graph.bfs("d", EdgeDirection::Incoming).collect();
+        assert_eq!(up, ["d", "b", "c", "a"]);
+        assert_eq!(graph.dfs("z", EdgeDirection::Incoming).count(), 0);
+    }
+}
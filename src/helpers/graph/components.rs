@@ -0,0 +1,251 @@
+//! Strongly connected components of a `DiGraph`, found with Tarjan's
+//! algorithm.
+//!
+//! Two vertices are in the same component if each can be reached from the
+//! other. Collapsing every component into a single vertex gives the
+//! condensation of the graph, which has no cycle, so cyclic inputs can still be
+//! ordered, weighed or counted through it.
+//!
+//! ```
+//! use adv20::helpers::graph::Graph;
+//! let mut graph = Graph::new();
+//! graph.add_edge("a", "b", 1);
+//! graph.add_edge("b", "a", 1);
+//! graph.add_edge("b", "c", 1);
+//! let components = graph.strongly_connected_components();
+//! assert_eq!(components.len(), 2);
+//! assert!(components.same_component("a", "b"));
+//! assert_eq!(components.condensation().topological_order().unwrap(), [&0, &1]);
+//! ```
+
+use std::collections::HashSet;
+
+use super::{DiGraph, Name};
+
+/// The strongly connected components of a graph, numbered in topological
+/// order: edges between two components always go to the higher number.
+/// Created by `DiGraph::strongly_connected_components`.
+pub struct Components<'a, N: ToOwned + ?Sized, W> {
+    graph: &'a DiGraph<N, W>,
+    /// component of every vertex, by vertex index.
+    component_of: Vec<usize>,
+    /// vertex indexes of every component.
+    members: Vec<Vec<usize>>,
+}
+
+//--------------------------------------------------------------------
+// Implementation
+//--------------------------------------------------------------------
+
+impl<N, W> DiGraph<N, W>
+where
+    N: Name + ?Sized,
+    W: Copy,
+{
+    /// Returns the strongly connected components of the graph.
+    pub fn strongly_connected_components(&self) -> Components<'_, N, W> {
+        let len = self.vertices.len();
+        let mut index: Vec<Option<usize>> = vec![None; len];
+        let mut low = vec![0; len];
+        let mut on_stack = vec![false; len];
+        let mut stack = Vec::new();
+        let mut members = Vec::new();
+        let mut counter = 0;
+
+        for root in 0..len {
+            if index[root].is_some() {
+                continue;
+            }
+            // the vertices being explored, each with the position of its next
+            // edge, replacing the recursion of the textbook algorithm
+            let mut calls = vec![(root, 0)];
+            while let Some(&(v, next)) = calls.last() {
+                if next == 0 {
+                    index[v] = Some(counter);
+                    low[v] = counter;
+                    counter += 1;
+                    stack.push(v);
+                    on_stack[v] = true;
+                }
+                match self.vertices[v].points_to.get(next) {
+                    Some(edge) => {
+                        calls.last_mut().unwrap().1 += 1;
+                        let w = edge.to_vertex;
+                        match index[w] {
+                            None => calls.push((w, 0)),
+                            Some(w_index) if on_stack[w] => low[v] = low[v].min(w_index),
+                            Some(_) => {}
+                        }
+                    }
+                    None => {
+                        calls.pop();
+                        if let Some(&(parent, _)) = calls.last() {
+                            low[parent] = low[parent].min(low[v]);
+                        }
+                        if Some(low[v]) == index[v] {
+                            let mut component = Vec::new();
+                            loop {
+                                let w = stack.pop().unwrap();
+                                on_stack[w] = false;
+                                component.push(w);
+                                if w == v {
+                                    break;
+                                }
+                            }
+                            members.push(component);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Tarjan's algorithm completes the components in reverse topological
+        // order
+        members.reverse();
+        let mut component_of = vec![0; len];
+        for (c, component) in members.iter_mut().enumerate() {
+            component.sort_unstable();
+            component.iter().for_each(|&v| component_of[v] = c);
+        }
+        Components {
+            graph: self,
+            component_of,
+            members,
+        }
+    }
+}
+
+impl<'a, N, W> Components<'a, N, W>
+where
+    N: Name + ?Sized,
+    W: Copy,
+{
+    /// returns the number of components.
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// returns the component of the vertex `name`.
+    pub fn component(&self, name: &N) -> Option<usize> {
+        self.graph.vertex(name).map(|idx| self.component_of[idx])
+    }
+
+    /// returns the names of the vertices of component `c`, in the order they
+    /// were added to the graph.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no component `c`.
+    pub fn members(&self, c: usize) -> impl Iterator<Item = &'a N> + '_ {
+        self.members[c].iter().map(move |&idx| self.graph.name(idx))
+    }
+
+    /// returns `true` if both vertices exist and are in the same component.
+    pub fn same_component(&self, a: &N, b: &N) -> bool {
+        match (self.component(a), self.component(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// returns `true` if component `c` contains a cycle: it has more than one
+    /// vertex, or a single one pointing to itself.
+    pub fn is_cyclic(&self, c: usize) -> bool {
+        match self.members[c].as_slice() {
+            [v] => self.graph.vertices[*v]
+                .points_to
+                .iter()
+                .any(|e| e.to_vertex == *v),
+            _ => true,
+        }
+    }
+
+    /// Returns the condensation of the graph: one vertex per component, named
+    /// after its number, and one unweighted edge between two components if any
+    /// edge joins their vertices.
+    pub fn condensation(&self) -> DiGraph<usize, ()> {
+        let mut dag = DiGraph::new();
+        for c in 0..self.len() {
+            dag.insert_vertex(&c);
+        }
+        let mut linked = HashSet::new();
+        for (from, v) in self.graph.vertices.iter().enumerate() {
+            let from = self.component_of[from];
+            for e in &v.points_to {
+                let to = self.component_of[e.to_vertex];
+                if from != to && linked.insert((from, to)) {
+                    dag.add_edge(&from, &to, ());
+                }
+            }
+        }
+        dag
+    }
+}
+
+//--------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::super::{tests::diamond, Graph};
+
+    #[test]
+    fn test_components() {
+        // the diamond with b <-> d, d -> e, e -> e and a lone f
+        let mut graph = diamond();
+        graph.add_edge("d", "b", 1);
+        graph.add_edge("d", "e", 1);
+        graph.add_edge("e", "e", 1);
+        graph.insert_vertex("f");
+        let components = graph.strongly_connected_components();
+        assert_eq!(components.len(), 5);
+        assert!(components.same_component("b", "d"));
+        assert!(components.same_component("d", "b"));
+        assert!(!components.same_component("a", "b"));
+        assert!(!components.same_component("a", "z"));
+
+        let bd = components.component("b").unwrap();
+        assert_eq!(components.members(bd).collect::<Vec<_>>(), ["b", "d"]);
+        assert!(components.is_cyclic(bd));
+        assert!(components.is_cyclic(components.component("e").unwrap()));
+        assert!(!components.is_cyclic(components.component("f").unwrap()));
+        // numbered in topological order
+        assert!(components.component("a") < components.component("c"));
+        assert!(components.component("c") < components.component("b"));
+        assert!(components.component("b") < components.component("e"));
+    }
+
+    #[test]
+    fn test_condensation() {
+        let mut graph = diamond();
+        graph.add_edge("d", "b", 1);
+        graph.add_edge("d", "e", 1);
+        let components = graph.strongly_connected_components();
+        let dag = components.condensation();
+        // a -> bd, a -> c, c -> bd and bd -> e
+        assert_eq!((dag.vertex_count(), dag.edge_count()), (4, 4));
+        let a = components.component("a").unwrap();
+        let e = components.component("e").unwrap();
+        assert_eq!(dag.count_paths(&a, &e), Ok(Some(2)));
+        assert!(dag.topological_order().is_ok());
+        assert!(dag.edges().all(|(from, to, _)| from < to));
+    }
+
+    #[test]
+    fn test_deep_components() {
+        // a single cycle through 100_000 vertices
+        let len = 100_000;
+        let mut graph = Graph::new();
+        for i in 0..len {
+            graph.add_edge(&i.to_string(), &((i + 1) % len).to_string(), 1);
+        }
+        let components = graph.strongly_connected_components();
+        assert_eq!(components.len(), 1);
+        assert_eq!(components.members(0).count(), len);
+    }
+}
@@ -0,0 +1,141 @@
+//! Export of a `DiGraph` to the Graphviz DOT language, e.g. to render it with
+//! `dot -Tsvg graph.dot -o graph.svg`. Edges of unweighted graphs, such as a
+//! condensation, have no label.
+//!
+//! ```
+//! use adv20::helpers::graph::Graph;
+//! let mut graph = Graph::new();
+//! graph.add_edge("a", "b", 3);
+//! assert_eq!(
+//!     graph.to_dot(),
+//!     "digraph {\n    v0 [label=\"a\"];\n    v1 [label=\"b\"];\n    v0 -> v1 [label=\"3\"];\n}\n"
+//! );
+//! ```
+
+use std::{collections::HashSet, fmt::Display, fmt::Write};
+
+use super::{DiGraph, EdgeDirection, EdgeLabel, Name};
+
+/// fill colour of the highlighted vertex.
+const CHOSEN_COLOUR: &str = "gold";
+/// fill colour, and edge colour, of the vertices related to it.
+const RELATED_COLOUR: &str = "lightblue";
+
+impl<N, W> DiGraph<N, W>
+where
+    N: Name + Display + ?Sized,
+    W: Copy + EdgeLabel,
+{
+    /// Returns a DOT document with one node per vertex, labelled with its
+    /// name, and one edge per graph edge, labelled with its weight.
+    pub fn to_dot(&self) -> String {
+        self.dot(None, &HashSet::new())
+    }
+
+    /// Same as `to_dot`, filling `name` and the vertices reachable from it in
+    /// `direction`, its descendants or ancestors, and colouring the edges
+    /// between them. Nothing is highlighted if there is no such vertex.
+    pub fn to_dot_highlighting(&self, name: &N, direction: EdgeDirection) -> String {
+        match self.vertex(name) {
+            Some(idx) => self.dot(Some(idx), &self.reachable(idx, direction)),
+            None => self.to_dot(),
+        }
+    }
+
+    //------------------------------
+    // Helpers
+    //------------------------------
+
+    fn dot(&self, chosen: Option<usize>, related: &HashSet<usize>) -> String {
+        let highlighted = |idx| chosen == Some(idx) || related.contains(&idx);
+        let mut out = String::from("digraph {\n");
+        for idx in 0..self.vertices.len() {
+            let fill = match (chosen == Some(idx), related.contains(&idx)) {
+                (true, _) => format!(", style=filled, fillcolor={}", CHOSEN_COLOUR),
+                (false, true) => format!(", style=filled, fillcolor={}", RELATED_COLOUR),
+                (false, false) => String::new(),
+            };
+            let label = escape(&self.name(idx).to_string());
+            let _ = writeln!(out, "    v{} [label=\"{}\"{}];", idx, label, fill);
+        }
+        for (idx, v) in self.vertices.iter().enumerate() {
+            for e in &v.points_to {
+                let mut attributes = Vec::new();
+                if let Some(weight) = e.weight.label() {
+                    attributes.push(format!("label=\"{}\"", escape(&weight)));
+                }
+                if highlighted(idx) && highlighted(e.to_vertex) {
+                    attributes.push(format!("color={}", RELATED_COLOUR));
+                }
+                let attributes = match attributes.is_empty() {
+                    true => String::new(),
+                    false => format!(" [{}]", attributes.join(", ")),
+                };
+                let _ = writeln!(out, "    v{} -> v{}{};", idx, e.to_vertex, attributes);
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// escapes `s` for use inside a quoted DOT string.
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+//--------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::super::{tests::diamond, EdgeDirection, Graph};
+
+    #[test]
+    fn test_to_dot() {
+        let dot = diamond().to_dot();
+        assert!(dot.starts_with("digraph {\n") && dot.ends_with("}\n"));
+        assert!(dot.contains("    v3 [label=\"d\"];\n"));
+        assert!(dot.contains("    v2 -> v3 [label=\"5\"];\n"));
+        assert_eq!(dot.lines().count(), 2 + 4 + 4);
+
+        let mut graph = Graph::new();
+        graph.add_edge("say \"hi\"", "back\\slash", 1);
+        assert!(graph.to_dot().contains("[label=\"say \\\"hi\\\"\"]"));
+        assert!(graph.to_dot().contains("[label=\"back\\\\slash\"]"));
+    }
+
+    #[test]
+    fn test_unweighted() {
+        // components a, c and b <-> d
+        let mut graph = diamond();
+        graph.add_edge("d", "b", 1);
+        let dag = graph.strongly_connected_components().condensation();
+        let dot = dag.to_dot();
+        assert!(dot.contains("    v0 [label=\"0\"];\n"));
+        assert!(dot.contains("    v0 -> v1;\n"));
+        let dot = dag.to_dot_highlighting(&2, EdgeDirection::Incoming);
+        assert!(dot.contains("    v1 -> v2 [color=lightblue];\n"));
+    }
+
+    #[test]
+    fn test_highlighting() {
+        let graph = diamond();
+        let dot = graph.to_dot_highlighting("d", EdgeDirection::Incoming);
+        assert!(dot.contains("    v3 [label=\"d\", style=filled, fillcolor=gold];"));
+        assert!(dot.contains("    v0 [label=\"a\", style=filled, fillcolor=lightblue];"));
+        assert!(dot.contains("    v0 -> v1 [label=\"2\", color=lightblue];"));
+        assert_eq!(dot.matches("fillcolor").count(), 4);
+
+        let dot = graph.to_dot_highlighting("b", EdgeDirection::Outgoing);
+        assert_eq!(dot.matches("fillcolor").count(), 2);
+        assert!(dot.contains("    v0 -> v1 [label=\"2\"];"));
+        assert!(dot.contains("    v1 -> v3 [label=\"4\", color=lightblue];"));
+        assert!(dot.contains("    v2 -> v3 [label=\"5\"];"));
+        assert_eq!(
+            graph.to_dot_highlighting("z", EdgeDirection::Outgoing),
+            graph.to_dot()
+        );
+    }
+}
@@ -0,0 +1,457 @@
+//! Saving and loading a `DiGraph` as text, so graphs can be shared between
+//! days, tools and test fixtures.
+//!
+//! Two formats are supported:
+//!
+//! - an edge list, one `from weight to` edge per line. A line holding a single
+//!   name adds a vertex without edges, blank lines are ignored. Names and
+//!   weights that are empty, hold whitespace or start with a quote are written
+//!   as JSON strings.
+//!
+//! - adjacency JSON, an object mapping every vertex name to the list of its
+//!   outgoing edges as `[to, weight]` pairs, e.g. `{"a": [["b", 2]], "b": []}`.
+//!   Names are JSON strings and weights are written as they are displayed.
+//!
+//! Unweighted graphs, with `()` weights, are saved and loaded without the
+//! weights, as `from to` lines and lists of names.
+//!
+//! Loaded vertices get their indexes in order of appearance, which may differ
+//! from the ones of the saved graph.
+//!
+//! ```
+//! use adv20::helpers::graph::Graph;
+//! let graph = Graph::from_edge_list("shinygold 1 darkolive\nshinygold 2 vibrantplum\n").unwrap();
+//! assert_eq!(graph.weigh_successors("shinygold").unwrap(), 3);
+//! assert_eq!(Graph::from_json(&graph.to_json()).unwrap().to_edge_list(), graph.to_edge_list());
+//! ```
+
+use std::{
+    borrow::{Borrow, Cow},
+    fmt::Display,
+    fmt::Write,
+    str::FromStr,
+};
+
+use super::{DiGraph, EdgeLabel, Name};
+use crate::helpers::read::parse::{self, Cursor, ParseError};
+
+//--------------------------------------------------------------------
+// Saving
+//--------------------------------------------------------------------
+
+impl<N, W> DiGraph<N, W>
+where
+    N: Name + Display + ?Sized,
+    W: Copy + EdgeLabel,
+{
+    /// Returns the graph as an edge list, vertices in index order, each one
+    /// followed by its outgoing edges.
+    pub fn to_edge_list(&self) -> String {
+        let mut out = String::new();
+        for (idx, v) in self.vertices.iter().enumerate() {
+            let name = self.name(idx).to_string();
+            let name = list_token(&name);
+            if v.points_to.is_empty() && v.pointed_by.is_empty() {
+                let _ = writeln!(out, "{}", name);
+            }
+            for e in &v.points_to {
+                let to = self.name(e.to_vertex).to_string();
+                let to = list_token(&to);
+                let _ = match e.weight.label() {
+                    Some(weight) => writeln!(out, "{} {} {}", name, list_token(&weight), to),
+                    None => writeln!(out, "{} {}", name, to),
+                };
+            }
+        }
+        out
+    }
+
+    /// Returns the graph as adjacency JSON, one vertex per line in index
+    /// order.
+    pub fn to_json(&self) -> String {
+        if self.vertices.is_empty() {
+            return String::from("{}\n");
+        }
+        let mut out = String::from("{\n");
+        for (idx, v) in self.vertices.iter().enumerate() {
+            let edges: Vec<String> = v
+                .points_to
+                .iter()
+                .map(|e| {
+                    let to = json_string(&self.name(e.to_vertex).to_string());
+                    match e.weight.label() {
+                        Some(weight) => format!("[{}, {}]", to, weight),
+                        None => to,
+                    }
+                })
+                .collect();
+            let comma = if idx + 1 < self.vertices.len() {
+                ","
+            } else {
+                ""
+            };
+            let name = json_string(&self.name(idx).to_string());
+            let _ = writeln!(out, "  {}: [{}]{}", name, edges.join(", "), comma);
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+//--------------------------------------------------------------------
+// Loading
+//--------------------------------------------------------------------
+
+impl<N, W> DiGraph<N, W>
+where
+    N: Name + ?Sized,
+    N::Owned: FromStr,
+    W: Copy + EdgeLabel,
+{
+    /// Builds a graph from an edge list.
+    pub fn from_edge_list(input: &str) -> Result<Self, ParseError> {
+        let mut graph = DiGraph::new();
+        let lines = parse::lines(input, |c| {
+            c.whitespace();
+            if c.is_empty() {
+                return Ok(None);
+            }
+            let from: N::Owned = list_value(c)?;
+            c.whitespace();
+            if c.is_empty() {
+                return Ok(Some((from, None)));
+            }
+            let start = *c;
+            let label = list_value::<String>(c)?;
+            c.whitespace();
+            if c.is_empty() {
+                // a `from to` line, only valid for unweighted graphs
+                let weight = W::from_label(None).ok_or_else(|| c.error("a token"))?;
+                let to = label.parse().map_err(|_| start.error("a valid value"))?;
+                return Ok(Some((from, Some((weight, to)))));
+            }
+            let weight = W::from_label(Some(&label)).ok_or_else(|| start.error("a valid value"))?;
+            let to: N::Owned = list_value(c)?;
+            c.whitespace();
+            Ok(Some((from, Some((weight, to)))))
+        })?;
+        for (from, edge) in lines.into_iter().flatten() {
+            match edge {
+                Some((weight, to)) => graph.add_edge(from.borrow(), to.borrow(), weight),
+                None => {
+                    graph.insert_vertex(from.borrow());
+                }
+            }
+        }
+        Ok(graph)
+    }
+
+    /// Builds a graph from adjacency JSON. Only the shape written by `to_json`
+    /// is accepted, with any whitespace. Vertices are added in the order of
+    /// the keys.
+    pub fn from_json(input: &str) -> Result<Self, ParseError> {
+        let mut graph = DiGraph::new();
+        let mut edges = Vec::new();
+        let mut c = Cursor::new(input);
+        c.whitespace();
+        c.literal("{")?;
+        c.whitespace();
+        if c.attempt(|c| c.literal("}")).is_none() {
+            loop {
+                let from: N::Owned = json_name(&mut c)?;
+                graph.insert_vertex(from.borrow());
+                c.whitespace();
+                c.literal(":")?;
+                c.whitespace();
+                c.literal("[")?;
+                c.whitespace();
+                if c.attempt(|c| c.literal("]")).is_none() {
+                    loop {
+                        let (to, weight) = json_edge::<N::Owned, W>(&mut c)?;
+                        c.whitespace();
+                        edges.push((from.borrow().to_owned(), to, weight));
+                        if !next_item(&mut c) {
+                            break;
+                        }
+                    }
+                    c.literal("]")?;
+                }
+                c.whitespace();
+                if !next_item(&mut c) {
+                    break;
+                }
+            }
+            c.literal("}")?;
+        }
+        c.whitespace();
+        c.end()?;
+
+        // edges are added last, so vertices keep the order of the keys
+        for (from, to, weight) in edges {
+            graph.add_edge(from.borrow(), to.borrow(), weight);
+        }
+        Ok(graph)
+    }
+}
+
+//--------------------------------------------------------------------
+// Helpers
+//--------------------------------------------------------------------
+
+/// returns `s` as an edge list token, quoted as a JSON string if it could
+/// not be read back otherwise.
+fn list_token(s: &str) -> Cow<'_, str> {
+    if s.is_empty() || s.starts_with('"') || s.contains(char::is_whitespace) {
+        Cow::Owned(json_string(s))
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
+/// parses an edge list token, quoted or not, with `FromStr`.
+fn list_value<T: FromStr>(c: &mut Cursor) -> Result<T, ParseError> {
+    if !c.rest().starts_with('"') {
+        return c.value();
+    }
+    let start = *c;
+    let parsed = parse_json_string(c)?.parse();
+    parsed.map_err(|_| {
+        *c = start;
+        c.error("a valid value")
+    })
+}
+
+/// returns `s` as a quoted JSON string.
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// parses a quoted JSON string.
+fn parse_json_string(c: &mut Cursor) -> Result<String, ParseError> {
+    c.literal("\"")?;
+    let mut out = String::new();
+    loop {
+        let ch = match c.any_char()? {
+            '"' => return Ok(out),
+            '\\' => match c.any_char()? {
+                'n' => '\n',
+                't' => '\t',
+                'r' => '\r',
+                'b' => '\u{8}',
+                'f' => '\u{c}',
+                'u' => {
+                    let hex: String = (0..4).map(|_| c.any_char()).collect::<Result<_, _>>()?;
+                    u32::from_str_radix(&hex, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| c.error("a valid unicode escape"))?
+                }
+                escaped @ '"' | escaped @ '\\' | escaped @ '/' => escaped,
+                _ => return Err(c.error("a valid escape")),
+            },
+            ch => ch,
+        };
+        out.push(ch);
+    }
+}
+
+/// parses a JSON string into a vertex name.
+fn json_name<N: FromStr>(c: &mut Cursor) -> Result<N, ParseError> {
+    let start = *c;
+    let parsed = parse_json_string(c)?.parse();
+    parsed.map_err(|_| {
+        *c = start;
+        c.error("a valid name")
+    })
+}
+
+/// parses an edge of adjacency JSON, either a `[to, weight]` pair or, for
+/// unweighted graphs, a bare name.
+fn json_edge<N: FromStr, W: EdgeLabel>(c: &mut Cursor) -> Result<(N, W), ParseError> {
+    if c.rest().starts_with('"') {
+        let start = *c;
+        let to = json_name(c)?;
+        let weight = W::from_label(None).ok_or_else(|| start.error("'['"))?;
+        return Ok((to, weight));
+    }
+    c.literal("[")?;
+    c.whitespace();
+    let to = json_name(c)?;
+    c.whitespace();
+    c.literal(",")?;
+    c.whitespace();
+    let weight = json_weight(c)?;
+    c.whitespace();
+    c.literal("]")?;
+    Ok((to, weight))
+}
+
+/// parses a JSON number, or any other unquoted value, into a weight.
+fn json_weight<W: EdgeLabel>(c: &mut Cursor) -> Result<W, ParseError> {
+    let start = *c;
+    let text = c.take_while(|ch| !matches!(ch, ',' | ']' | '}') && !ch.is_whitespace());
+    W::from_label(Some(text)).ok_or_else(|| start.error("a valid weight"))
+}
+
+/// consumes the comma, and the whitespace after it, separating two items of
+/// a JSON array or object. Returns `false` if there is none.
+fn next_item(c: &mut Cursor) -> bool {
+    let found = c.attempt(|c| c.literal(",")).is_some();
+    c.whitespace();
+    found
+}
+
+//--------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::super::{tests::diamond, DiGraph, Graph};
+
+    #[test]
+    fn test_edge_list() {
+        let mut graph = diamond();
+        graph.insert_vertex("e");
+        let text = graph.to_edge_list();
+        assert_eq!(text, "a 2 b\na 3 c\nb 4 d\nc 5 d\ne\n");
+        let loaded = Graph::from_edge_list(&text).unwrap();
+        assert_eq!(loaded.to_edge_list(), text);
+        assert_eq!(loaded.vertex_count(), 5);
+
+        let loaded = Graph::from_edge_list("  x\t1   y \n\n y 2 x\n").unwrap();
+        assert_eq!(loaded.edge_count(), 2);
+        assert!(loaded.topological_order().is_err());
+    }
+
+    #[test]
+    fn test_edge_list_quoting() {
+        let mut graph = Graph::new();
+        graph.add_edge("light red", "\"bright\"", 1);
+        graph.add_edge("", "tab\there", 2);
+        let text = graph.to_edge_list();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(
+            lines,
+            [r#""light red" 1 "\"bright\"""#, r#""" 2 "tab\there""#]
+        );
+        let loaded = Graph::from_edge_list(&text).unwrap();
+        assert_eq!(loaded.to_edge_list(), text);
+        assert!(loaded.contains("") && loaded.contains("\"bright\""));
+
+        let err = Graph::from_edge_list("\"a b 1 c").err().unwrap();
+        assert_eq!(err.expected, "a character");
+    }
+
+    #[test]
+    fn test_edge_list_errors() {
+        let err = Graph::from_edge_list("a 1 b\na x b").err().unwrap();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.expected, "a valid value");
+        let err = Graph::from_edge_list("a 1").err().unwrap();
+        assert_eq!(err.expected, "a token");
+        let err = Graph::from_edge_list("a 1 b c").err().unwrap();
+        assert_eq!(err.expected, "end of input");
+    }
+
+    #[test]
+    fn test_json() {
+        let mut graph = diamond();
+        graph.insert_vertex("e");
+        let json = graph.to_json();
+        assert_eq!(
+            json,
+            "{\n  \"a\": [[\"b\", 2], [\"c\", 3]],\n  \"b\": [[\"d\", 4]],\n  \"c\": [[\"d\", 5]],\n  \"d\": [],\n  \"e\": []\n}\n"
+        );
+        let loaded = Graph::from_json(&json).unwrap();
+        assert_eq!(loaded.to_json(), json);
+        assert_eq!(loaded.vertex("e"), graph.vertex("e"));
+
+        let compact = Graph::from_json(r#"{"a":[["b",1]],"b":[["a",2]]}"#).unwrap();
+        assert_eq!(compact.to_edge_list(), "a 1 b\nb 2 a\n");
+        assert_eq!(Graph::from_json(" {} ").unwrap().vertex_count(), 0);
+        assert_eq!(Graph::new().to_json(), "{}\n");
+    }
+
+    #[test]
+    fn test_json_escapes() {
+        let mut graph = Graph::new();
+        graph.add_edge("say \"hi\"", "tab\there\\", 1);
+        let loaded = Graph::from_json(&graph.to_json()).unwrap();
+        assert!(loaded.contains("say \"hi\"") && loaded.contains("tab\there\\"));
+        let loaded = Graph::from_json(r#"{"caf\u00e9": []}"#).unwrap();
+        assert!(loaded.contains("café"));
+    }
+
+    #[test]
+    fn test_json_errors() {
+        let err = Graph::from_json("{\n  \"a\": [[\"b\", -1]]\n}")
+            .err()
+            .unwrap();
+        assert_eq!((err.line, err.column), (2, 15));
+        assert_eq!(err.expected, "a valid weight");
+        let err = Graph::from_json(r#"{"a": [["b", 1]]"#).err().unwrap();
+        assert_eq!(err.expected, "'}'");
+        let err = DiGraph::<u8, u8>::from_json(r#"{"300": []}"#)
+            .err()
+            .unwrap();
+        assert_eq!((err.column, err.expected.as_str()), (2, "a valid name"));
+    }
+
+    #[test]
+    fn test_generic_round_trip() {
+        let mut graph = DiGraph::<u8, u64>::new();
+        graph.add_edge(&0, &1, 1 << 40);
+        graph.add_edge(&1, &4, 1);
+        let loaded = DiGraph::<u8, u64>::from_edge_list(&graph.to_edge_list()).unwrap();
+        assert_eq!(loaded.successors(&0).collect::<Vec<_>>(), [(&1, 1 << 40)]);
+        let loaded = DiGraph::<u8, u64>::from_json(&graph.to_json()).unwrap();
+        assert_eq!(loaded.to_json(), graph.to_json());
+    }
+
+    #[test]
+    fn test_unweighted() {
+        let mut graph = DiGraph::<u8, ()>::new();
+        graph.add_edge(&0, &1, ());
+        graph.add_edge(&0, &4, ());
+        graph.insert_vertex(&7);
+        let text = graph.to_edge_list();
+        assert_eq!(text, "0 1\n0 4\n7\n");
+        let json = graph.to_json();
+        assert_eq!(
+            json,
+            "{\n  \"0\": [\"1\", \"4\"],\n  \"1\": [],\n  \"4\": [],\n  \"7\": []\n}\n"
+        );
+        let loaded = DiGraph::<u8, ()>::from_edge_list(&text).unwrap();
+        assert_eq!(loaded.to_edge_list(), text);
+        let loaded = DiGraph::<u8, ()>::from_json(&json).unwrap();
+        assert_eq!(loaded.to_json(), json);
+
+        // the two shapes are only read into graphs of the matching kind
+        let err = DiGraph::<u8, ()>::from_edge_list("0 1 4").err().unwrap();
+        assert_eq!((err.column, err.expected.as_str()), (3, "a valid value"));
+        let err = Graph::from_edge_list(&text).err().unwrap();
+        assert_eq!((err.column, err.expected.as_str()), (4, "a token"));
+        let err = DiGraph::<u8, ()>::from_json(r#"{"0": [["1", 2]]}"#)
+            .err()
+            .unwrap();
+        assert_eq!((err.column, err.expected.as_str()), (14, "a valid weight"));
+        let err = Graph::from_json(&json).err().unwrap();
+        assert_eq!((err.line, err.column, err.expected.as_str()), (2, 9, "'['"));
+    }
+}
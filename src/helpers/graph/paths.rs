@@ -0,0 +1,243 @@
+//! Path searches between named vertices of a `DiGraph`.
+//!
+//! ```
+//! use adv20::helpers::graph::Graph;
+//! let mut graph = Graph::new();
+//! graph.add_edge("a", "b", 1);
+//! graph.add_edge("b", "c", 1);
+//! graph.add_edge("a", "c", 5);
+//! let path = graph.shortest_path("a", "c").unwrap();
+//! assert_eq!(path.cost, 2);
+//! assert_eq!(path.vertices, ["a", "b", "c"]);
+//! assert_eq!(graph.fewest_edges_path("a", "c").unwrap().cost, 1);
+//! assert_eq!(graph.count_paths("a", "c"), Ok(Some(2)));
+//! ```
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashSet, VecDeque},
+};
+
+use super::{Cycle, DiGraph, EdgeDirection, Name};
+
+/// The result of a successful search.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Path<'a, N: ?Sized> {
+    /// total weight of the edges, or number of edges for unweighted searches.
+    pub cost: u64,
+    /// visited vertices, from start to goal, both included.
+    pub vertices: Vec<&'a N>,
+}
+
+// derived, it would need `N: Clone`, which `str` names are not
+impl<N: ?Sized> Clone for Path<'_, N> {
+    fn clone(&self) -> Self {
+        Path {
+            cost: self.cost,
+            vertices: self.vertices.clone(),
+        }
+    }
+}
+
+//--------------------------------------------------------------------
+// Searches
+//--------------------------------------------------------------------
+
+impl<N, W> DiGraph<N, W>
+where
+    N: Name + ?Sized,
+    W: Copy,
+{
+    /// Breadth first search for the path with the fewest edges from `start`
+    /// to `goal`, ignoring weights.
+    ///
+    /// Returns `None` if either vertex does not exist or `goal` cannot be
+    /// reached from `start`.
+    pub fn fewest_edges_path(&self, start: &N, goal: &N) -> Option<Path<'_, N>> {
+        let (start, goal) = (self.vertex(start)?, self.vertex(goal)?);
+        let mut prev: Vec<Option<usize>> = vec![None; self.vertices.len()];
+        let mut dist: Vec<Option<u64>> = vec![None; self.vertices.len()];
+        let mut queue = VecDeque::new();
+        dist[start] = Some(0);
+        queue.push_back(start);
+
+        while let Some(idx) = queue.pop_front() {
+            if idx == goal {
+                break;
+            }
+            for edge in &self.vertices[idx].points_to {
+                if dist[edge.to_vertex].is_none() {
+                    dist[edge.to_vertex] = Some(dist[idx].unwrap() + 1);
+                    prev[edge.to_vertex] = Some(idx);
+                    queue.push_back(edge.to_vertex);
+                }
+            }
+        }
+
+        Some(self.build_path(&prev, dist[goal]?, goal))
+    }
+
+    /// Returns, for every vertex, the set of vertices reachable from it.
+    /// Both the list and the sets are indexed as returned by `vertex`. A vertex
+    /// only reaches itself through a cycle.
+    pub fn reachability(&self) -> Vec<HashSet<usize>> {
+        (0..self.vertices.len())
+            .map(|idx| self.reachable(idx, EdgeDirection::Outgoing))
+            .collect()
+    }
+
+    /// Counts the distinct paths from `start` to `goal`. The count of every
+    /// vertex is computed once, so shared sub-graphs are not walked again.
+    ///
+    /// Returns zero if either vertex does not exist and `None` if the count
+    /// overflows a `u64`. Fails if a cycle can be reached from `start`, as the
+    /// count could be infinite.
+    pub fn count_paths(&self, start: &N, goal: &N) -> Result<Option<u64>, Cycle<N::Owned>> {
+        let (start, goal) = match (self.vertex(start), self.vertex(goal)) {
+            (Some(start), Some(goal)) => (start, goal),
+            _ => return Ok(Some(0)),
+        };
+        let mut counts = vec![0u64; self.vertices.len()];
+        for idx in self.post_order(Some(start))? {
+            counts[idx] = if idx == goal {
+                1
+            } else {
+                let count = self.vertices[idx]
+                    .points_to
+                    .iter()
+                    .try_fold(0u64, |sum, e| sum.checked_add(counts[e.to_vertex]));
+                match count {
+                    Some(count) => count,
+                    None => return Ok(None),
+                }
+            };
+        }
+        Ok(Some(counts[start]))
+    }
+
+    /// returns the path ending at `goal` described by `prev`, the vertex each
+    /// one was reached from.
+    fn build_path(&self, prev: &[Option<usize>], cost: u64, goal: usize) -> Path<'_, N> {
+        let mut vertices = vec![self.name(goal)];
+        let mut idx = goal;
+        while let Some(p) = prev[idx] {
+            vertices.push(self.name(p));
+            idx = p;
+        }
+        vertices.reverse();
+        Path { cost, vertices }
+    }
+}
+
+impl<N, W> DiGraph<N, W>
+where
+    N: Name + ?Sized,
+    W: Copy + Into<u64>,
+{
+    /// Dijkstra's algorithm, returning the path from `start` to `goal` with the
+    /// lowest total weight.
+    ///
+    /// Returns `None` if either vertex does not exist or `goal` cannot be
+    /// reached from `start`. Paths whose cost overflows a `u64` are ignored.
+    pub fn shortest_path(&self, start: &N, goal: &N) -> Option<Path<'_, N>> {
+        let (start, goal) = (self.vertex(start)?, self.vertex(goal)?);
+        let mut prev: Vec<Option<usize>> = vec![None; self.vertices.len()];
+        let mut dist: Vec<Option<u64>> = vec![None; self.vertices.len()];
+        let mut heap = BinaryHeap::new();
+        dist[start] = Some(0);
+        heap.push(Reverse((0, start)));
+
+        while let Some(Reverse((d, idx))) = heap.pop() {
+            if idx == goal {
+                break;
+            }
+            if dist[idx].is_some_and(|best| d > best) {
+                continue;
+            }
+            for edge in &self.vertices[idx].points_to {
+                let next_d = match d.checked_add(edge.weight.into()) {
+                    Some(next_d) => next_d,
+                    None => continue,
+                };
+                if dist[edge.to_vertex].is_none_or(|best| next_d < best) {
+                    dist[edge.to_vertex] = Some(next_d);
+                    prev[edge.to_vertex] = Some(idx);
+                    heap.push(Reverse((next_d, edge.to_vertex)));
+                }
+            }
+        }
+
+        Some(self.build_path(&prev, dist[goal]?, goal))
+    }
+}
+
+//--------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        tests::{diamond, ladder},
+        DiGraph,
+    };
+
+    #[test]
+    fn test_shortest_paths() {
+        let mut graph = diamond();
+        graph.add_edge("a", "d", 20);
+        let path = graph.shortest_path("a", "d").unwrap();
+        assert_eq!(path.cost, 6);
+        assert_eq!(path.vertices, ["a", "b", "d"]);
+        let path = graph.fewest_edges_path("a", "d").unwrap();
+        assert_eq!(path.cost, 1);
+        assert_eq!(path.vertices, ["a", "d"]);
+
+        assert_eq!(graph.shortest_path("a", "a").unwrap().vertices, ["a"]);
+        assert!(graph.shortest_path("d", "a").is_none());
+        assert!(graph.fewest_edges_path("a", "z").is_none());
+    }
+
+    #[test]
+    fn test_shortest_path_overflow() {
+        let mut graph = DiGraph::<str, u64>::new();
+        graph.add_edge("a", "b", u64::MAX);
+        graph.add_edge("b", "c", 1);
+        assert_eq!(graph.shortest_path("a", "b").unwrap().cost, u64::MAX);
+        assert!(graph.shortest_path("a", "c").is_none());
+        graph.add_edge("a", "c", 7);
+        assert_eq!(graph.shortest_path("a", "c").unwrap().vertices, ["a", "c"]);
+    }
+
+    #[test]
+    fn test_reachability() {
+        let mut graph = diamond();
+        graph.add_edge("x", "x", 1);
+        let reach = graph.reachability();
+        let idx = |name| graph.vertex(name).unwrap();
+        assert!(reach[idx("a")].contains(&idx("d")));
+        assert!(!reach[idx("d")].contains(&idx("a")));
+        assert!(!reach[idx("a")].contains(&idx("a")));
+        assert!(reach[idx("x")].contains(&idx("x")));
+        assert_eq!(reach[idx("a")].len(), 3);
+        assert!(reach[idx("d")].is_empty());
+    }
+
+    #[test]
+    fn test_count_paths() {
+        let mut graph = diamond();
+        assert_eq!(graph.count_paths("a", "d"), Ok(Some(2)));
+        assert_eq!(graph.count_paths("b", "d"), Ok(Some(1)));
+        assert_eq!(graph.count_paths("d", "a"), Ok(Some(0)));
+        assert_eq!(graph.count_paths("a", "a"), Ok(Some(1)));
+        assert_eq!(graph.count_paths("a", "z"), Ok(Some(0)));
+        graph.add_edge("d", "c", 1);
+        assert!(graph.count_paths("a", "d").is_err());
+    }
+
+    #[test]
+    fn test_count_paths_overflow() {
+        assert_eq!(ladder(63).count_paths("v0", "v63"), Ok(Some(1 << 63)));
+        assert_eq!(ladder(64).count_paths("v0", "v64"), Ok(None));
+    }
+}
@@ -1,4 +1,9 @@
-use std::{convert::TryInto, fmt::Debug, ops::Index};
+use std::{
+    convert::TryInto,
+    fmt::Debug,
+    ops::{Index, IndexMut},
+    thread,
+};
 
 use super::base2d::Base2d;
 
@@ -19,25 +24,31 @@ use super::base2d::Base2d;
 ///
 /// # Indexing
 ///
-/// Implements the Index trait, so the grid may be read by a tuple inside
-/// square brackets. Example:
+/// Implements the Index and IndexMut traits, so the grid may be accessed by a
+/// tuple, or a Base2d, inside square brackets. Example:
 ///
 /// ```
 /// use adv20::helpers::grid::Grid;
 /// let mut grid = Grid::new(5, 5, 0u8);
 /// let v = grid.get_mut(2, 2);
 /// *v = 100;
-/// assert_eq!(grid[(2,2)], 100);
+/// grid[(3usize, 1usize)] = 7;
+/// assert_eq!(grid[(2, 2)], 100);
+/// assert_eq!(grid.try_get((3, 1)), Some(&7));
+/// assert_eq!(grid.try_get((-1, 0)), None);
 /// ```
 ///
 /// ## Beware
 ///
 /// If no inferring is made, the Default type for tuples in rust is i32.
+/// Tuples of unsigned types up to `u16` and `usize` convert infallibly, any
+/// other integer type is checked.
 ///
 /// # Panics
 ///
-/// Panics if the indexing inside square brackets is done with negative values.
-#[derive(Debug, PartialEq, Eq)]
+/// Panics if the indexing inside square brackets is done with negative or out
+/// of bounds values. Use `try_get` for a checked access.
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Grid<T> {
     flat: Vec<T>,
     pub len_x: usize,
@@ -65,35 +76,63 @@ impl<T> Grid<T> {
     ///
     /// Panics if either index is out of bounds.
     pub fn get(&self, x: usize, y: usize) -> &T {
-        &self.flat[self.index(x, y)]
+        &self.flat[self.bounded_index(x, y)]
     }
 
-    /// returns the value at position pos, where pos any type that can be
-    /// represented as a Base2d<usize>.
+    /// returns the value at position pos, where pos is any type that can be
+    /// converted into a Base2d<usize>, e.g. a `(usize, usize)` or a `(i32,
+    /// i32)` tuple.
     ///
     /// # Panics
     ///
-    /// Panics if indexes are out of bounds.
+    /// Panics if the conversion fails (negative values) or if indexes are out
+    /// of bounds.
     pub fn get_from2d<V>(&self, pos: V) -> &T
     where
-        V: Into<Base2d<usize>>,
+        V: TryInto<Base2d<usize>>,
+        V::Error: Debug,
     {
-        let pos: Base2d<usize> = pos.into();
+        let pos = pos.try_into().expect("Invalid grid position.");
         self.get(pos.x, pos.y)
     }
 
     pub fn get_mut(&mut self, x: usize, y: usize) -> &mut T {
-        let i = self.index(x, y); // must have an aux variable coz mutable borrow
+        let i = self.bounded_index(x, y); // must have an aux variable coz mutable borrow
         &mut self.flat[i]
     }
 
+    /// mutable version of `get_from2d`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the conversion fails (negative values) or if indexes are out
+    /// of bounds.
     pub fn get_mut_from2d<V>(&mut self, p: V) -> &mut T
     where
-        V: Into<Base2d<usize>>,
+        V: TryInto<Base2d<usize>>,
+        V::Error: Debug,
     {
-        let p: Base2d<usize> = p.into();
-        let i = self.index(p.x, p.y);
-        &mut self.flat[i]
+        let p = p.try_into().expect("Invalid grid position.");
+        self.get_mut(p.x, p.y)
+    }
+
+    /// returns the value at position pos, or `None` if pos cannot be
+    /// converted into a Base2d<usize> or is out of bounds.
+    pub fn try_get<V>(&self, pos: V) -> Option<&T>
+    where
+        V: TryInto<Base2d<usize>>,
+    {
+        let pos = self.checked_index(pos)?;
+        Some(&self.flat[pos])
+    }
+
+    /// mutable version of `try_get`.
+    pub fn try_get_mut<V>(&mut self, pos: V) -> Option<&mut T>
+    where
+        V: TryInto<Base2d<usize>>,
+    {
+        let pos = self.checked_index(pos)?;
+        Some(&mut self.flat[pos])
     }
 
     /// returns the total size of the array (len_x * len_y)
@@ -131,6 +170,106 @@ impl<T> Grid<T> {
         self.get(x, ny)
     }
 
+    /// returns an iterator over the values of the grid in row major order.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.flat.iter()
+    }
+
+    /// returns an iterator over mutable references to the values of the grid
+    /// in row major order.
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.flat.iter_mut()
+    }
+
+    /// returns an iterator over the values of the grid along with their
+    /// positions, in row major order.
+    pub fn enumerate(&self) -> impl Iterator<Item = (Base2d<usize>, &T)> {
+        let len_x = self.len_x;
+        self.flat
+            .iter()
+            .enumerate()
+            .map(move |(i, v)| (Base2d::new(i % len_x, i / len_x), v))
+    }
+
+    /// builds a new grid with the same dimensions by applying `f` to every
+    /// value.
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            flat: self.flat.iter().map(f).collect(),
+            len_x: self.len_x,
+            len_y: self.len_y,
+        }
+    }
+
+    /// calls `f` with the index and a mutable slice of every row, splitting
+    /// the rows in contiguous chunks processed by scoped threads, one chunk per
+    /// available core.
+    ///
+    /// Worth it only for large grids, where the work per row outweighs the
+    /// cost of spawning the threads.
+    pub fn par_for_each_row_mut<F>(&mut self, f: F)
+    where
+        T: Send,
+        F: Fn(usize, &mut [T]) + Sync,
+    {
+        if self.len_x == 0 || self.len_y == 0 {
+            return;
+        }
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let rows_per_chunk = self.len_y.div_ceil(threads);
+        let len_x = self.len_x;
+        let f = &f;
+        thread::scope(|scope| {
+            for (chunk_idx, chunk) in self.flat.chunks_mut(rows_per_chunk * len_x).enumerate() {
+                scope.spawn(move || {
+                    for (i, row) in chunk.chunks_mut(len_x).enumerate() {
+                        f(chunk_idx * rows_per_chunk + i, row);
+                    }
+                });
+            }
+        });
+    }
+
+    /// returns an iterator over the elements of one of the grid borders.
+    ///
+    /// Top and bottom edges are read from left to right, left and right edges
+    /// from top to bottom.
+    pub fn edge(&self, side: Side) -> impl Iterator<Item = &T> {
+        let len = match side {
+            Side::Top | Side::Bottom => self.len_x,
+            Side::Left | Side::Right => self.len_y,
+        };
+        (0..len).map(move |i| match side {
+            Side::Top => self.get(i, 0),
+            Side::Bottom => self.get(i, self.len_y - 1),
+            Side::Left => self.get(0, i),
+            Side::Right => self.get(self.len_x - 1, i),
+        })
+    }
+
+    /// returns a borrowed view of the rectangle starting at `x, y` with size
+    /// `len_x` by `len_y`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rectangle does not fit inside the grid.
+    pub fn sub_grid(&self, x: usize, y: usize, len_x: usize, len_y: usize) -> SubGrid<'_, T> {
+        assert!(
+            x + len_x <= self.len_x && y + len_y <= self.len_y,
+            "Sub grid out of bounds."
+        );
+        SubGrid {
+            grid: self,
+            x0: x,
+            y0: y,
+            len_x,
+            len_y,
+        }
+    }
+
     //--------------------------------------------------------------------
     // Private
     //--------------------------------------------------------------------
@@ -140,17 +279,199 @@ impl<T> Grid<T> {
     fn index(&self, x: usize, y: usize) -> usize {
         self.len_x * y + x
     }
+
+    /// same as `index`, checking each coordinate against its dimension so a
+    /// position past the end of a row does not wrap into the next one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either coordinate is out of bounds.
+    fn bounded_index(&self, x: usize, y: usize) -> usize {
+        assert!(
+            x < self.len_x && y < self.len_y,
+            "Grid position ({}, {}) out of bounds for a {}x{} grid.",
+            x,
+            y,
+            self.len_x,
+            self.len_y
+        );
+        self.index(x, y)
+    }
+
+    /// returns the index for acessing the `flat` array from `pos`, or `None`
+    /// if `pos` is not a valid position inside the grid.
+    fn checked_index<V>(&self, pos: V) -> Option<usize>
+    where
+        V: TryInto<Base2d<usize>>,
+    {
+        let pos = pos.try_into().ok()?;
+        if pos.x < self.len_x && pos.y < self.len_y {
+            Some(self.index(pos.x, pos.y))
+        } else {
+            None
+        }
+    }
 }
 
 impl<T, V> Index<V> for Grid<T>
 where
     V: TryInto<Base2d<usize>>,
-    <V as TryInto<Base2d<usize>>>::Error: Debug,
+    V::Error: Debug,
 {
     type Output = T;
 
     fn index(&self, index: V) -> &Self::Output {
-        self.get_from2d(index.try_into().unwrap())
+        self.get_from2d(index)
+    }
+}
+
+impl<T, V> IndexMut<V> for Grid<T>
+where
+    V: TryInto<Base2d<usize>>,
+    V::Error: Debug,
+{
+    fn index_mut(&mut self, index: V) -> &mut Self::Output {
+        self.get_mut_from2d(index)
+    }
+}
+
+//--------------------------------------------------------------------
+// Transformations
+//--------------------------------------------------------------------
+
+/// One of the four borders of a grid.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Side {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+impl<T: Clone> Grid<T> {
+    /// returns a copy of the grid with rows and columns swapped.
+    pub fn transpose(&self) -> Grid<T> {
+        self.remap(self.len_y, self.len_x, |x, y| (y, x))
+    }
+
+    /// returns a copy of the grid rotated 90 degrees clockwise.
+    pub fn rotate(&self) -> Grid<T> {
+        let len_y = self.len_y;
+        self.remap(self.len_y, self.len_x, |x, y| (y, len_y - 1 - x))
+    }
+
+    /// returns a copy of the grid mirrored around the vertical axis, i.e. each
+    /// row is reversed.
+    pub fn flip_x(&self) -> Grid<T> {
+        let len_x = self.len_x;
+        self.remap(self.len_x, self.len_y, |x, y| (len_x - 1 - x, y))
+    }
+
+    /// returns a copy of the grid mirrored around the horizontal axis, i.e.
+    /// the row order is reversed.
+    pub fn flip_y(&self) -> Grid<T> {
+        let len_y = self.len_y;
+        self.remap(self.len_x, self.len_y, |x, y| (x, len_y - 1 - y))
+    }
+
+    /// returns the eight dihedral orientations of the grid: the four
+    /// clockwise rotations followed by the four rotations of the flipped grid.
+    /// The first element is a copy of the grid itself.
+    pub fn orientations(&self) -> Vec<Grid<T>> {
+        let mut out = Vec::with_capacity(8);
+        for start in [self.clone(), self.flip_x()].iter() {
+            let mut g = start.clone();
+            for _ in 0..4 {
+                let next = g.rotate();
+                out.push(g);
+                g = next;
+            }
+        }
+        out
+    }
+
+    /// joins a grid of tiles into a single grid. Tile `(i, j)` is placed at
+    /// column `i` and row `j` of the output.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tiles do not all have the same dimensions.
+    pub fn stitch(tiles: &Grid<Grid<T>>) -> Grid<T> {
+        let first = tiles.get(0, 0);
+        let (tile_x, tile_y) = (first.len_x, first.len_y);
+        assert!(
+            tiles
+                .flat
+                .iter()
+                .all(|t| t.len_x == tile_x && t.len_y == tile_y),
+            "Tiles must have the same dimensions."
+        );
+        let len_x = tiles.len_x * tile_x;
+        let len_y = tiles.len_y * tile_y;
+        let mut flat = Vec::with_capacity(len_x * len_y);
+        for y in 0..len_y {
+            for x in 0..len_x {
+                let tile = tiles.get(x / tile_x, y / tile_y);
+                flat.push(tile.get(x % tile_x, y % tile_y).clone());
+            }
+        }
+        Grid::new_with_vec(len_x, len_y, flat)
+    }
+
+    /// builds a `len_x` by `len_y` grid where the element at `x, y` is copied
+    /// from the position returned by `source(x, y)`.
+    fn remap<F>(&self, len_x: usize, len_y: usize, source: F) -> Grid<T>
+    where
+        F: Fn(usize, usize) -> (usize, usize),
+    {
+        let mut flat = Vec::with_capacity(len_x * len_y);
+        for y in 0..len_y {
+            for x in 0..len_x {
+                let (sx, sy) = source(x, y);
+                flat.push(self.get(sx, sy).clone());
+            }
+        }
+        Grid::new_with_vec(len_x, len_y, flat)
+    }
+}
+
+//--------------------------------------------------------------------
+// Sub Grid
+//--------------------------------------------------------------------
+
+/// A borrowed rectangular view into a `Grid`. Coordinates are relative to the
+/// top left corner of the view.
+#[derive(Debug, Clone, Copy)]
+pub struct SubGrid<'a, T> {
+    grid: &'a Grid<T>,
+    x0: usize,
+    y0: usize,
+    pub len_x: usize,
+    pub len_y: usize,
+}
+
+impl<'a, T> SubGrid<'a, T> {
+    /// returns the value at position x,y of the view.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of the view bounds.
+    pub fn get(&self, x: usize, y: usize) -> &'a T {
+        assert!(x < self.len_x && y < self.len_y, "Index out of bounds.");
+        self.grid.get(self.x0 + x, self.y0 + y)
+    }
+
+    /// returns an iterator over the elements of row `y` of the view.
+    pub fn row(&self, y: usize) -> impl Iterator<Item = &'a T> + '_ {
+        (0..self.len_x).map(move |x| self.get(x, y))
+    }
+}
+
+impl<'a, T: Clone> SubGrid<'a, T> {
+    /// copies the view into a new `Grid`.
+    pub fn to_grid(&self) -> Grid<T> {
+        let flat = (0..self.len_y).flat_map(|y| self.row(y).cloned()).collect();
+        Grid::new_with_vec(self.len_x, self.len_y, flat)
     }
 }
 
@@ -164,4 +485,123 @@ pub mod tests {
         println!("{}", usize::try_from(-3i32).unwrap());
         let _ = grid[(3, 3)];
     }
+
+    fn sample() -> Grid<u8> {
+        // 1 2 3
+        // 4 5 6
+        Grid::new_with_vec(3, 2, vec![1, 2, 3, 4, 5, 6])
+    }
+
+    #[test]
+    fn test_transformations() {
+        let grid = sample();
+        assert_eq!(
+            grid.transpose(),
+            Grid::new_with_vec(2, 3, vec![1, 4, 2, 5, 3, 6])
+        );
+        assert_eq!(
+            grid.rotate(),
+            Grid::new_with_vec(2, 3, vec![4, 1, 5, 2, 6, 3])
+        );
+        assert_eq!(
+            grid.flip_x(),
+            Grid::new_with_vec(3, 2, vec![3, 2, 1, 6, 5, 4])
+        );
+        assert_eq!(
+            grid.flip_y(),
+            Grid::new_with_vec(3, 2, vec![4, 5, 6, 1, 2, 3])
+        );
+        assert_eq!(grid.rotate().rotate().rotate().rotate(), grid);
+
+        let orientations = grid.orientations();
+        assert_eq!(orientations.len(), 8);
+        assert!(orientations.contains(&grid.transpose()));
+        assert!(orientations.contains(&grid.flip_y()));
+    }
+
+    #[test]
+    fn test_edges_and_views() {
+        let grid = sample();
+        assert_eq!(grid.edge(Side::Top).copied().collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(
+            grid.edge(Side::Bottom).copied().collect::<Vec<_>>(),
+            [4, 5, 6]
+        );
+        assert_eq!(grid.edge(Side::Left).copied().collect::<Vec<_>>(), [1, 4]);
+        assert_eq!(grid.edge(Side::Right).copied().collect::<Vec<_>>(), [3, 6]);
+
+        let view = grid.sub_grid(1, 0, 2, 2);
+        assert_eq!(*view.get(1, 1), 6);
+        assert_eq!(view.to_grid(), Grid::new_with_vec(2, 2, vec![2, 3, 5, 6]));
+    }
+
+    #[test]
+    fn test_stitch() {
+        let tiles = Grid::new_with_vec(2, 1, vec![sample(), sample().flip_y()]);
+        let stitched = Grid::stitch(&tiles);
+        assert_eq!(
+            stitched,
+            Grid::new_with_vec(6, 2, vec![1, 2, 3, 4, 5, 6, 4, 5, 6, 1, 2, 3])
+        );
+    }
+
+    #[test]
+    fn test_iteration() {
+        let mut grid = sample();
+        assert_eq!(grid.iter().sum::<u8>(), 21);
+        grid.iter_mut().for_each(|v| *v *= 2);
+        let (pos, &v) = grid.enumerate().nth(4).unwrap();
+        assert_eq!((pos.tuple(), v), ((1, 1), 10));
+        assert_eq!(
+            grid.map(|&v| v > 6),
+            Grid::new_with_vec(3, 2, vec![false, false, false, true, true, true])
+        );
+    }
+
+    #[test]
+    fn test_par_for_each_row_mut() {
+        let mut grid = Grid::new(7, 301, 0usize);
+        grid.par_for_each_row_mut(|y, row| {
+            for (x, v) in row.iter_mut().enumerate() {
+                *v = y * 7 + x;
+            }
+        });
+        assert!(grid.enumerate().all(|(p, &v)| v == p.y * 7 + p.x));
+    }
+
+    #[test]
+    fn test_positions() {
+        let mut grid = sample();
+        assert_eq!(*grid.get_from2d((1usize, 1usize)), 5);
+        assert_eq!(*grid.get_from2d(Base2d::new(2usize, 0)), 3);
+        assert_eq!(grid[(0i64, 1i64)], 4);
+        grid[(0u8, 0u8)] = 9;
+        *grid.get_mut_from2d((1, 0)) = 8;
+        assert_eq!(grid.iter().take(2).collect::<Vec<_>>(), [&9, &8]);
+        assert_eq!(grid.try_get((-1, 0)), None);
+        assert_eq!(grid.try_get((3, 0)), None);
+        assert_eq!(grid.try_get((0, 2)), None);
+        *grid.try_get_mut((2, 1)).unwrap() = 0;
+        assert_eq!(grid[(2, 1)], 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_negative_index() {
+        let _ = sample()[(-1, 0)];
+    }
+
+    #[test]
+    #[should_panic(expected = "Grid position (3, 0) out of bounds for a 3x2 grid.")]
+    fn test_index_past_row_end() {
+        // would be the first value of the second row without the per axis check
+        let _ = sample()[(3usize, 0usize)];
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn test_mut_index_out_of_bounds() {
+        let mut grid = sample();
+        *grid.get_mut_from2d((0, 2)) = 1;
+    }
 }
@@ -0,0 +1,340 @@
+//! N-dimensional grids generalising `Grid` to any number of dimensions.
+//!
+//! Two flavours are provided:
+//!
+//! - `GridNd`, a dense grid with a fixed size per dimension, backed by a flat
+//!   `Vec` in the same fashion as `Grid`;
+//!
+//! - `SparseGrid`, a set of active coordinates backed by a `HashSet`. It has no
+//!   fixed size, its bounding box grows on demand as cells are activated.
+//!
+//! Both share the same neighbour API: every cell has `3^N - 1` neighbours,
+//! diagonals included.
+
+use std::collections::{HashMap, HashSet};
+
+/// A dense three-dimensional grid.
+pub type Grid3d<T> = GridNd<T, 3>;
+/// A dense four-dimensional grid.
+pub type Grid4d<T> = GridNd<T, 4>;
+/// A sparse three-dimensional grid.
+pub type SparseGrid3d = SparseGrid<3>;
+/// A sparse four-dimensional grid.
+pub type SparseGrid4d = SparseGrid<4>;
+
+//--------------------------------------------------------------------
+// Dense Grid
+//--------------------------------------------------------------------
+
+/// A N-dimensional array using a flat internal representation.
+///
+/// The first coordinate varies the fastest, so for `N = 2` the layout is the
+/// same row major one used by `Grid`.
+///
+/// ```
+/// use adv20::helpers::gridnd::Grid3d;
+/// let mut grid = Grid3d::new([3, 3, 3], 0u8);
+/// *grid.get_mut([1, 1, 1]) = 1;
+/// assert_eq!(grid.count_neighbours([0, 0, 0], |&v| v == 1), 1);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GridNd<T, const N: usize> {
+    flat: Vec<T>,
+    pub dims: [usize; N],
+}
+
+impl<T: Clone, const N: usize> GridNd<T, N> {
+    pub fn new(dims: [usize; N], init: T) -> Self {
+        GridNd {
+            flat: vec![init; dims.iter().product()],
+            dims,
+        }
+    }
+}
+
+impl<T, const N: usize> GridNd<T, N> {
+    /// # Panics
+    ///
+    /// Panics if `flat` does not hold exactly as many elements as the product
+    /// of `dims`.
+    pub fn new_with_vec(dims: [usize; N], flat: Vec<T>) -> Self {
+        assert_eq!(flat.len(), dims.iter().product::<usize>());
+        GridNd { flat, dims }
+    }
+
+    /// returns the value at position `pos`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any index is out of bounds.
+    pub fn get(&self, pos: [usize; N]) -> &T {
+        &self.flat[self.index(pos)]
+    }
+
+    pub fn get_mut(&mut self, pos: [usize; N]) -> &mut T {
+        let i = self.index(pos);
+        &mut self.flat[i]
+    }
+
+    /// returns the total number of cells in the grid.
+    pub fn size(&self) -> usize {
+        self.flat.len()
+    }
+
+    /// returns `true` if `pos` lies inside the grid.
+    pub fn contains(&self, pos: [i64; N]) -> bool {
+        pos.iter()
+            .zip(self.dims.iter())
+            .all(|(&p, &d)| p >= 0 && (p as usize) < d)
+    }
+
+    /// returns an iterator over the in-bounds neighbours of `pos`, diagonals
+    /// included.
+    pub fn neighbours(&self, pos: [usize; N]) -> impl Iterator<Item = [usize; N]> + '_ {
+        offsets::<N>().into_iter().filter_map(move |offset| {
+            let mut out = [0; N];
+            for i in 0..N {
+                let p = pos[i] as i64 + offset[i];
+                if p < 0 || p as usize >= self.dims[i] {
+                    return None;
+                }
+                out[i] = p as usize;
+            }
+            Some(out)
+        })
+    }
+
+    /// counts the neighbours of `pos` whose value satisfies `predicate`.
+    pub fn count_neighbours<P>(&self, pos: [usize; N], predicate: P) -> usize
+    where
+        P: Fn(&T) -> bool,
+    {
+        self.neighbours(pos)
+            .filter(|&n| predicate(self.get(n)))
+            .count()
+    }
+
+    /// returns an iterator over the coordinates and values of all cells.
+    pub fn iter(&self) -> impl Iterator<Item = ([usize; N], &T)> {
+        self.flat
+            .iter()
+            .enumerate()
+            .map(move |(i, v)| (self.position(i), v))
+    }
+
+    //--------------------------------------------------------------------
+    // Private
+    //--------------------------------------------------------------------
+
+    /// returns the index for acessing the `flat` array from the coordinates
+    /// `pos`.
+    fn index(&self, pos: [usize; N]) -> usize {
+        let mut idx = 0;
+        for i in (0..N).rev() {
+            assert!(pos[i] < self.dims[i], "Index out of bounds.");
+            idx = idx * self.dims[i] + pos[i];
+        }
+        idx
+    }
+
+    /// inverse of `index`.
+    fn position(&self, mut idx: usize) -> [usize; N] {
+        let mut pos = [0; N];
+        for (p, &d) in pos.iter_mut().zip(self.dims.iter()) {
+            *p = idx % d;
+            idx /= d;
+        }
+        pos
+    }
+}
+
+//--------------------------------------------------------------------
+// Sparse Grid
+//--------------------------------------------------------------------
+
+/// A N-dimensional grid of boolean cells storing only the active ones.
+///
+/// Coordinates are signed and unbounded; `bounds` reports the smallest box
+/// enclosing every cell ever activated.
+///
+/// ```
+/// use adv20::helpers::gridnd::SparseGrid3d;
+/// let mut grid = SparseGrid3d::new();
+/// grid.activate([0, 0, 0]);
+/// grid.activate([-1, 2, 0]);
+/// assert_eq!(grid.bounds(), Some(([-1, 0, 0], [0, 2, 0])));
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct SparseGrid<const N: usize> {
+    active: HashSet<[i64; N]>,
+    min: Option<[i64; N]>,
+    max: Option<[i64; N]>,
+}
+
+impl<const N: usize> SparseGrid<N> {
+    pub fn new() -> Self {
+        SparseGrid {
+            active: HashSet::new(),
+            min: None,
+            max: None,
+        }
+    }
+
+    pub fn is_active(&self, pos: [i64; N]) -> bool {
+        self.active.contains(&pos)
+    }
+
+    /// activates the cell at `pos`, growing the bounding box if needed.
+    pub fn activate(&mut self, pos: [i64; N]) {
+        if self.active.insert(pos) {
+            self.grow(pos);
+        }
+    }
+
+    pub fn deactivate(&mut self, pos: [i64; N]) {
+        self.active.remove(&pos);
+    }
+
+    /// returns the number of active cells.
+    pub fn count(&self) -> usize {
+        self.active.len()
+    }
+
+    /// returns the `(min, max)` corners, both inclusive, of the bounding box.
+    /// `None` if no cell has been activated yet.
+    pub fn bounds(&self) -> Option<([i64; N], [i64; N])> {
+        Some((self.min?, self.max?))
+    }
+
+    /// returns an iterator over the neighbours of `pos`, diagonals included.
+    pub fn neighbours(&self, pos: [i64; N]) -> impl Iterator<Item = [i64; N]> {
+        offsets::<N>().into_iter().map(move |offset| {
+            let mut out = pos;
+            out.iter_mut().zip(offset.iter()).for_each(|(p, o)| *p += o);
+            out
+        })
+    }
+
+    /// counts the active neighbours of `pos`.
+    pub fn count_neighbours(&self, pos: [i64; N]) -> usize {
+        self.neighbours(pos).filter(|n| self.is_active(*n)).count()
+    }
+
+    /// returns an iterator over the active cells, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &[i64; N]> {
+        self.active.iter()
+    }
+
+    /// Runs one step of a cellular automaton and returns the resulting grid.
+    ///
+    /// `rule` receives whether a cell is currently active and its number of
+    /// active neighbours, and returns whether the cell is active afterwards.
+    /// Only active cells and their neighbours are visited, so inactive cells
+    /// must stay inactive when they have no active neighbour.
+    pub fn step<R>(&self, rule: R) -> Self
+    where
+        R: Fn(bool, usize) -> bool,
+    {
+        let mut counts = HashMap::<[i64; N], usize>::new();
+        for &pos in &self.active {
+            counts.entry(pos).or_insert(0);
+            for n in self.neighbours(pos) {
+                *counts.entry(n).or_insert(0) += 1;
+            }
+        }
+
+        let mut next = SparseGrid::new();
+        for (pos, count) in counts {
+            if rule(self.is_active(pos), count) {
+                next.activate(pos);
+            }
+        }
+        next
+    }
+
+    //--------------------------------------------------------------------
+    // Private
+    //--------------------------------------------------------------------
+
+    fn grow(&mut self, pos: [i64; N]) {
+        let min = self.min.get_or_insert(pos);
+        let max = self.max.get_or_insert(pos);
+        for i in 0..N {
+            min[i] = min[i].min(pos[i]);
+            max[i] = max[i].max(pos[i]);
+        }
+    }
+}
+
+//--------------------------------------------------------------------
+// Helpers
+//--------------------------------------------------------------------
+
+/// returns the `3^N - 1` offsets to the neighbours of a cell.
+fn offsets<const N: usize>() -> Vec<[i64; N]> {
+    let mut out = vec![[0i64; N]];
+    for i in 0..N {
+        out = out
+            .into_iter()
+            .flat_map(|o| {
+                (-1..=1).map(move |d| {
+                    let mut o = o;
+                    o[i] = d;
+                    o
+                })
+            })
+            .collect();
+    }
+    out.retain(|o| o.iter().any(|&d| d != 0));
+    out
+}
+
+//--------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offsets() {
+        assert_eq!(offsets::<2>().len(), 8);
+        assert_eq!(offsets::<3>().len(), 26);
+        assert_eq!(offsets::<4>().len(), 80);
+    }
+
+    #[test]
+    fn test_dense() {
+        let mut grid = Grid3d::new([2, 3, 4], 0u8);
+        assert_eq!(grid.size(), 24);
+        *grid.get_mut([1, 2, 3]) = 7;
+        assert_eq!(*grid.get([1, 2, 3]), 7);
+        assert_eq!(grid.iter().find(|(_, &v)| v == 7).unwrap().0, [1, 2, 3]);
+        assert_eq!(grid.neighbours([0, 0, 0]).count(), 7);
+        assert_eq!(grid.count_neighbours([1, 1, 2], |&v| v == 7), 1);
+        assert!(grid.contains([1, 2, 3]));
+        assert!(!grid.contains([-1, 0, 0]));
+    }
+
+    #[test]
+    fn test_sparse_step() {
+        // glider from the 3d conway cubes example, 6 cycles leave 112 cubes
+        let mut grid = SparseGrid3d::new();
+        for &(x, y) in &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            grid.activate([x, y, 0]);
+        }
+        let rule = |active, n| matches!((active, n), (true, 2) | (_, 3));
+        for _ in 0..6 {
+            grid = grid.step(rule);
+        }
+        assert_eq!(grid.count(), 112);
+
+        let mut grid = SparseGrid4d::new();
+        for &(x, y) in &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            grid.activate([x, y, 0, 0]);
+        }
+        grid = grid.step(rule);
+        assert_eq!(grid.count(), 29);
+    }
+}
@@ -0,0 +1,347 @@
+//! Conversion of a `Grid` to and from the plain (ASCII) variants of the netpbm
+//! formats, for visual debugging of simulations.
+//!
+//! - PPM (`P3`) holds colour images, every cell mapped to an `[r, g, b]`;
+//!
+//! - PGM (`P2`) holds grayscale images, every cell mapped to a single `u8`.
+//!
+//! Most image viewers open both formats. Lines are wrapped to the 70
+//! characters allowed by the formats. When reading, comments are skipped and
+//! samples are scaled from the maximum value of the image to 255. `Animation`
+//! writes one numbered frame per call into a directory, which can be turned
+//! into a video with any external tool.
+
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use super::grid::Grid;
+
+/// An RGB colour.
+pub type Rgb = [u8; 3];
+
+/// longest line allowed by the plain formats.
+const MAX_LINE_LEN: usize = 70;
+
+//--------------------------------------------------------------------
+// Writers
+//--------------------------------------------------------------------
+
+/// writes `grid` as a plain PPM image, mapping each cell to a colour with
+/// `colour`.
+///
+/// ```
+/// use adv20::helpers::{grid::Grid, image};
+/// let grid = Grid::new_with_vec(2, 1, vec![true, false]);
+/// let mut out = Vec::new();
+/// image::write_ppm(&grid, &mut out, |&v| if v { [0, 128, 0] } else { [255; 3] }).unwrap();
+/// assert_eq!(String::from_utf8(out).unwrap(), "P3\n2 1\n255\n0 128 0 255 255 255\n");
+/// ```
+pub fn write_ppm<T, W, F>(grid: &Grid<T>, writer: &mut W, colour: F) -> io::Result<()>
+where
+    W: Write,
+    F: Fn(&T) -> Rgb,
+{
+    writeln!(writer, "P3\n{} {}\n255", grid.len_x, grid.len_y)?;
+    write_rows(grid, writer, |v| {
+        let [r, g, b] = colour(v);
+        format!("{} {} {}", r, g, b)
+    })
+}
+
+/// writes `grid` as a plain PGM image, mapping each cell to a gray level with
+/// `gray`.
+pub fn write_pgm<T, W, F>(grid: &Grid<T>, writer: &mut W, gray: F) -> io::Result<()>
+where
+    W: Write,
+    F: Fn(&T) -> u8,
+{
+    writeln!(writer, "P2\n{} {}\n255", grid.len_x, grid.len_y)?;
+    write_rows(grid, writer, |v| gray(v).to_string())
+}
+
+/// saves `grid` as a plain PPM image at `path`.
+pub fn save_ppm<T, P, F>(grid: &Grid<T>, path: P, colour: F) -> io::Result<()>
+where
+    P: AsRef<Path>,
+    F: Fn(&T) -> Rgb,
+{
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_ppm(grid, &mut writer, colour)?;
+    writer.flush()
+}
+
+/// saves `grid` as a plain PGM image at `path`.
+pub fn save_pgm<T, P, F>(grid: &Grid<T>, path: P, gray: F) -> io::Result<()>
+where
+    P: AsRef<Path>,
+    F: Fn(&T) -> u8,
+{
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_pgm(grid, &mut writer, gray)?;
+    writer.flush()
+}
+
+//--------------------------------------------------------------------
+// Readers
+//--------------------------------------------------------------------
+
+/// reads a plain PPM image into a grid, mapping each colour to a cell with
+/// `cell`.
+///
+/// ```
+/// use adv20::helpers::image;
+/// let input = "P3\n# a comment\n2 1\n255\n0 128 0 255 255 255\n";
+/// let grid = image::read_ppm(&mut input.as_bytes(), |rgb| rgb == [0, 128, 0]).unwrap();
+/// assert_eq!(grid.iter().copied().collect::<Vec<_>>(), [true, false]);
+/// ```
+pub fn read_ppm<T, R, F>(reader: &mut R, cell: F) -> io::Result<Grid<T>>
+where
+    T: Clone,
+    R: Read,
+    F: Fn(Rgb) -> T,
+{
+    let (len_x, len_y, samples) = read_samples(reader, "P3", 3)?;
+    let cells = samples.chunks(3).map(|c| cell([c[0], c[1], c[2]]));
+    Ok(Grid::new_with_vec(len_x, len_y, cells.collect()))
+}
+
+/// reads a plain PGM image into a grid, mapping each gray level to a cell with
+/// `cell`.
+pub fn read_pgm<T, R, F>(reader: &mut R, cell: F) -> io::Result<Grid<T>>
+where
+    T: Clone,
+    R: Read,
+    F: Fn(u8) -> T,
+{
+    let (len_x, len_y, samples) = read_samples(reader, "P2", 1)?;
+    let cells = samples.into_iter().map(cell);
+    Ok(Grid::new_with_vec(len_x, len_y, cells.collect()))
+}
+
+/// loads the plain PPM image at `path`.
+pub fn load_ppm<T, P, F>(path: P, cell: F) -> io::Result<Grid<T>>
+where
+    T: Clone,
+    P: AsRef<Path>,
+    F: Fn(Rgb) -> T,
+{
+    read_ppm(&mut File::open(path)?, cell)
+}
+
+/// loads the plain PGM image at `path`.
+pub fn load_pgm<T, P, F>(path: P, cell: F) -> io::Result<Grid<T>>
+where
+    T: Clone,
+    P: AsRef<Path>,
+    F: Fn(u8) -> T,
+{
+    read_pgm(&mut File::open(path)?, cell)
+}
+
+//--------------------------------------------------------------------
+// Animation
+//--------------------------------------------------------------------
+
+/// Writes the successive states of a simulation as numbered PPM frames,
+/// `frame_00000.ppm`, `frame_00001.ppm` and so on.
+pub struct Animation {
+    dir: PathBuf,
+    frame: usize,
+}
+
+impl Animation {
+    /// Creates the directory `dir`, and any missing parent, to hold the frames.
+    pub fn new<P: AsRef<Path>>(dir: P) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        Ok(Animation {
+            dir: dir.as_ref().to_path_buf(),
+            frame: 0,
+        })
+    }
+
+    /// writes the next frame and returns its path.
+    pub fn frame<T, F>(&mut self, grid: &Grid<T>, colour: F) -> io::Result<PathBuf>
+    where
+        F: Fn(&T) -> Rgb,
+    {
+        let path = self.dir.join(format!("frame_{:05}.ppm", self.frame));
+        save_ppm(grid, &path, colour)?;
+        self.frame += 1;
+        Ok(path)
+    }
+
+    /// returns the number of frames written so far.
+    pub fn len(&self) -> usize {
+        self.frame
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frame == 0
+    }
+}
+
+//--------------------------------------------------------------------
+// Helpers
+//--------------------------------------------------------------------
+
+/// writes every row on a new line, with the cells formatted by `cell` and
+/// separated by spaces. Rows are wrapped between two cells so no line is
+/// longer than `MAX_LINE_LEN`.
+fn write_rows<T, W, F>(grid: &Grid<T>, writer: &mut W, cell: F) -> io::Result<()>
+where
+    W: Write,
+    F: Fn(&T) -> String,
+{
+    let mut line = String::new();
+    for y in 0..grid.len_y {
+        for x in 0..grid.len_x {
+            let text = cell(grid.get(x, y));
+            if !line.is_empty() && line.len() + 1 + text.len() > MAX_LINE_LEN {
+                writeln!(writer, "{}", line)?;
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&text);
+        }
+        writeln!(writer, "{}", line)?;
+        line.clear();
+    }
+    Ok(())
+}
+
+/// reads a plain image with the magic number `magic` and `channels` samples
+/// per pixel, and returns its width, its height and its samples scaled to
+/// 255.
+fn read_samples<R: Read>(
+    reader: &mut R,
+    magic: &str,
+    channels: usize,
+) -> io::Result<(usize, usize, Vec<u8>)> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    let mut tokens = text
+        .lines()
+        .flat_map(|l| l.split('#').next().unwrap().split_whitespace());
+
+    match tokens.next() {
+        Some(m) if m == magic => {}
+        found => return Err(invalid(format!("expected {}, found {:?}", magic, found))),
+    }
+    let mut header = || -> io::Result<usize> {
+        let token = tokens
+            .next()
+            .ok_or_else(|| invalid("truncated header".into()))?;
+        token
+            .parse()
+            .map_err(|_| invalid(format!("invalid header value '{}'", token)))
+    };
+    let (len_x, len_y, max) = (header()?, header()?, header()?);
+    if max == 0 || max > 255 {
+        return Err(invalid(format!("unsupported maximum value {}", max)));
+    }
+
+    let samples = tokens
+        .map(|token| match token.parse::<usize>() {
+            Ok(v) if v <= max => Ok((v * 255 / max) as u8),
+            _ => Err(invalid(format!("invalid sample '{}'", token))),
+        })
+        .collect::<io::Result<Vec<u8>>>()?;
+    let expected = len_x * len_y * channels;
+    if samples.len() != expected {
+        let reason = format!("expected {} samples, found {}", expected, samples.len());
+        return Err(invalid(reason));
+    }
+    Ok((len_x, len_y, samples))
+}
+
+fn invalid(reason: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason)
+}
+
+//--------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_pgm() {
+        let grid = Grid::new_with_vec(2, 2, vec![0u8, 1, 2, 3]);
+        let mut out = Vec::new();
+        write_pgm(&grid, &mut out, |&v| v * 85).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "P2\n2 2\n255\n0 85\n170 255\n"
+        );
+    }
+
+    #[test]
+    fn test_wrapped_lines() {
+        let grid = Grid::new_with_vec(30, 2, (0..60).map(|v| v as u8 * 4).collect());
+        let mut out = Vec::new();
+        write_ppm(&grid, &mut out, |&v| [v, 255 - v, 100]).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.lines().all(|l| l.len() <= MAX_LINE_LEN));
+        // every row starts on a new line
+        assert!(text.contains("\n120 135 100 "));
+
+        let read = read_ppm(&mut text.as_bytes(), |[r, _, _]| r).unwrap();
+        assert_eq!((read.len_x, read.len_y), (30, 2));
+        assert!(read.iter().eq(grid.iter()));
+    }
+
+    #[test]
+    fn test_read_pgm() {
+        let grid = Grid::new_with_vec(3, 2, vec![0u8, 1, 2, 3, 2, 1]);
+        let mut out = Vec::new();
+        write_pgm(&grid, &mut out, |&v| v * 85).unwrap();
+        let read = read_pgm(&mut out.as_slice(), |v| v / 85).unwrap();
+        assert!(read.iter().eq(grid.iter()));
+
+        // comments anywhere and a maximum value other than 255
+        let input = "P2 # gray\n2 1 # size\n3\n0 3";
+        let read = read_pgm(&mut input.as_bytes(), |v| v).unwrap();
+        assert!(read.iter().eq([0, 255].iter()));
+    }
+
+    #[test]
+    fn test_read_errors() {
+        let read = |input: &str| read_pgm(&mut input.as_bytes(), |v| v).unwrap_err();
+        assert_eq!(
+            read("P3\n1 1\n255\n0 0 0").kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert!(read("P2\n1").to_string().contains("truncated header"));
+        assert!(read("P2\n2 1\n255\n0")
+            .to_string()
+            .contains("expected 2 samples"));
+        assert!(read("P2\n1 1\n15\n16")
+            .to_string()
+            .contains("invalid sample '16'"));
+        assert!(read("P2\n1 1\n256\n0")
+            .to_string()
+            .contains("maximum value"));
+    }
+
+    #[test]
+    fn test_animation() {
+        let dir = std::env::temp_dir().join(format!("adv20_animation_{}", std::process::id()));
+        let mut animation = Animation::new(&dir).unwrap();
+        let grid = Grid::new(2, 2, false);
+        animation.frame(&grid, |_| [0; 3]).unwrap();
+        let last = animation.frame(&grid, |_| [0; 3]).unwrap();
+        assert_eq!(animation.len(), 2);
+        assert_eq!(last, dir.join("frame_00001.ppm"));
+        assert!(fs::read_to_string(&last).unwrap().starts_with("P3\n2 2\n"));
+        let read = load_ppm(&last, |rgb| rgb == [0; 3]).unwrap();
+        assert!(read.iter().all(|&black| black));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,182 @@
+//! A grid without fixed bounds, indexed by signed coordinates.
+//!
+//! The grid is backed by a `Grid` covering the area written so far. Writing
+//! outside of it reallocates the storage, growing it in the needed directions
+//! with some slack so that walks do not reallocate on every step. Reading
+//! outside of it returns the fill value.
+
+use super::{base2d::Base2d, grid::Grid};
+
+/// A two-dimensional grid that grows on demand in any direction.
+///
+/// ```
+/// use adv20::helpers::{base2d::Base2d, infinite_grid::InfiniteGrid};
+/// let mut grid = InfiniteGrid::new('.');
+/// grid.set(Base2d::new(-2, 3), '#');
+/// assert_eq!(*grid.get(Base2d::new(-2, 3)), '#');
+/// assert_eq!(*grid.get(Base2d::new(100, -100)), '.');
+/// ```
+#[derive(Debug, Clone)]
+pub struct InfiniteGrid<T> {
+    storage: Grid<T>,
+    origin: Base2d<i64>, // coordinates of `storage` cell (0, 0)
+    fill: T,
+    bounds: Option<(Base2d<i64>, Base2d<i64>)>,
+}
+
+impl<T: Clone> InfiniteGrid<T> {
+    /// Constructs an empty grid where every cell holds `fill`.
+    pub fn new(fill: T) -> Self {
+        InfiniteGrid {
+            storage: Grid::new(0, 0, fill.clone()),
+            origin: Base2d::new(0, 0),
+            fill,
+            bounds: None,
+        }
+    }
+
+    /// returns the value at `pos`, or the fill value if the cell has never
+    /// been written.
+    pub fn get(&self, pos: Base2d<i64>) -> &T {
+        match self.local(pos) {
+            Some((x, y)) => self.storage.get(x, y),
+            None => &self.fill,
+        }
+    }
+
+    /// returns a mutable reference to the value at `pos`, growing the grid if
+    /// needed. The cell becomes part of the bounding box.
+    pub fn get_mut(&mut self, pos: Base2d<i64>) -> &mut T {
+        self.reserve(pos);
+        self.extend_bounds(pos);
+        let (x, y) = self.local(pos).unwrap();
+        self.storage.get_mut(x, y)
+    }
+
+    /// writes `value` at `pos`, growing the grid if needed.
+    pub fn set(&mut self, pos: Base2d<i64>, value: T) {
+        *self.get_mut(pos) = value;
+    }
+
+    /// returns the `(min, max)` corners, both inclusive, of the smallest box
+    /// enclosing every written cell. `None` if nothing has been written.
+    pub fn bounds(&self) -> Option<(Base2d<i64>, Base2d<i64>)> {
+        self.bounds
+    }
+
+    /// copies the bounding box into a fixed `Grid`. The cell at `min` of the
+    /// bounding box becomes the cell `(0, 0)` of the output.
+    pub fn to_grid(&self) -> Grid<T> {
+        let (min, max) = match self.bounds {
+            Some(b) => b,
+            None => return Grid::new(0, 0, self.fill.clone()),
+        };
+        let len_x = (max.x - min.x + 1) as usize;
+        let len_y = (max.y - min.y + 1) as usize;
+        let mut flat = Vec::with_capacity(len_x * len_y);
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                flat.push(self.get(Base2d::new(x, y)).clone());
+            }
+        }
+        Grid::new_with_vec(len_x, len_y, flat)
+    }
+
+    //--------------------------------------------------------------------
+    // Private
+    //--------------------------------------------------------------------
+
+    /// converts `pos` to storage coordinates, if it is inside the storage.
+    fn local(&self, pos: Base2d<i64>) -> Option<(usize, usize)> {
+        let x = pos.x - self.origin.x;
+        let y = pos.y - self.origin.y;
+        if x < 0 || y < 0 || x >= self.storage.len_x as i64 || y >= self.storage.len_y as i64 {
+            return None;
+        }
+        Some((x as usize, y as usize))
+    }
+
+    /// makes sure the storage covers `pos`. When it does not, each side that
+    /// must grow is extended by at least the current length of that dimension.
+    fn reserve(&mut self, pos: Base2d<i64>) {
+        if self.local(pos).is_some() {
+            return;
+        }
+        let (len_x, len_y) = (self.storage.len_x as i64, self.storage.len_y as i64);
+        let (mut x0, mut y0) = self.origin.tuple();
+        let (mut x1, mut y1) = (x0 + len_x, y0 + len_y); // exclusive
+
+        if len_x == 0 || len_y == 0 {
+            x0 = pos.x;
+            y0 = pos.y;
+            x1 = pos.x + 1;
+            y1 = pos.y + 1;
+        } else {
+            if pos.x < x0 {
+                x0 = pos.x.min(x0 - len_x);
+            } else if pos.x >= x1 {
+                x1 = (pos.x + 1).max(x1 + len_x);
+            }
+            if pos.y < y0 {
+                y0 = pos.y.min(y0 - len_y);
+            } else if pos.y >= y1 {
+                y1 = (pos.y + 1).max(y1 + len_y);
+            }
+        }
+
+        let mut storage = Grid::new((x1 - x0) as usize, (y1 - y0) as usize, self.fill.clone());
+        for y in 0..self.storage.len_y {
+            for x in 0..self.storage.len_x {
+                let nx = (self.origin.x - x0) as usize + x;
+                let ny = (self.origin.y - y0) as usize + y;
+                *storage.get_mut(nx, ny) = self.storage.get(x, y).clone();
+            }
+        }
+        self.storage = storage;
+        self.origin = Base2d::new(x0, y0);
+    }
+
+    fn extend_bounds(&mut self, pos: Base2d<i64>) {
+        let (min, max) = self.bounds.get_or_insert((pos, pos));
+        min.x = min.x.min(pos.x);
+        min.y = min.y.min(pos.y);
+        max.x = max.x.max(pos.x);
+        max.y = max.y.max(pos.y);
+    }
+}
+
+//--------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grow() {
+        let mut grid = InfiniteGrid::new(0u8);
+        assert!(grid.bounds().is_none());
+        grid.set(Base2d::new(0, 0), 1);
+        grid.set(Base2d::new(-3, 2), 2);
+        grid.set(Base2d::new(5, -7), 3);
+        *grid.get_mut(Base2d::new(0, 0)) += 10;
+
+        assert_eq!(*grid.get(Base2d::new(0, 0)), 11);
+        assert_eq!(*grid.get(Base2d::new(-3, 2)), 2);
+        assert_eq!(*grid.get(Base2d::new(5, -7)), 3);
+        assert_eq!(*grid.get(Base2d::new(-300, 2)), 0);
+
+        let (min, max) = grid.bounds().unwrap();
+        assert_eq!((min.tuple(), max.tuple()), ((-3, -7), (5, 2)));
+    }
+
+    #[test]
+    fn test_to_grid() {
+        let mut grid = InfiniteGrid::new('.');
+        grid.set(Base2d::new(-1, -1), '#');
+        grid.set(Base2d::new(1, 0), '#');
+        let fixed = grid.to_grid();
+        assert_eq!(fixed, Grid::new_with_vec(3, 2, "#....#".chars().collect()));
+    }
+}
@@ -0,0 +1,302 @@
+//! Shortest path searches and flood fill over a `Grid`.
+//!
+//! Movement is orthogonal only (up, down, left and right). The cell contents
+//! decide whether a cell can be entered, and at which cost, through a closure
+//! supplied by the caller.
+//!
+//! ```
+//! use adv20::helpers::{base2d::Base2d, grid::Grid, pathfinding};
+//! // . # .
+//! // . # .
+//! // . . .
+//! let grid = Grid::new_with_vec(3, 3, ".#..#....".chars().collect());
+//! let path = pathfinding::bfs(&grid, Base2d::new(0, 0), Base2d::new(2, 0), |&c| c != '#');
+//! assert_eq!(path.unwrap().cost, 6);
+//! ```
+
+use std::{cmp::Reverse, collections::BinaryHeap, collections::VecDeque};
+
+use super::{base2d::Base2d, grid::Grid};
+
+/// The result of a successful search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path {
+    /// total cost of the path, the start cell is not accounted for.
+    pub cost: u64,
+    /// visited cells, from start to goal, both included.
+    pub steps: Vec<Base2d<usize>>,
+}
+
+//--------------------------------------------------------------------
+// Searches
+//--------------------------------------------------------------------
+
+/// Breadth first search where every step costs one. `passable` tells whether
+/// a cell may be entered.
+///
+/// Returns `None` if `goal` cannot be reached from `start`.
+pub fn bfs<T, P>(
+    grid: &Grid<T>,
+    start: Base2d<usize>,
+    goal: Base2d<usize>,
+    passable: P,
+) -> Option<Path>
+where
+    P: Fn(&T) -> bool,
+{
+    let start_idx = index(grid, start);
+    let goal_idx = index(grid, goal);
+    let mut prev: Vec<Option<usize>> = vec![None; grid.size()];
+    let mut dist: Vec<Option<u64>> = vec![None; grid.size()];
+    let mut queue = VecDeque::new();
+    dist[start_idx] = Some(0);
+    queue.push_back(start_idx);
+
+    while let Some(idx) = queue.pop_front() {
+        if idx == goal_idx {
+            break;
+        }
+        for next in neighbours(grid, idx) {
+            if dist[next].is_none() && passable(grid.get(next % grid.len_x, next / grid.len_x)) {
+                dist[next] = Some(dist[idx].unwrap() + 1);
+                prev[next] = Some(idx);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    build_path(grid, &prev, dist[goal_idx]?, start_idx, goal_idx)
+}
+
+/// Dijkstra's algorithm. `cost` returns the cost of entering a cell, or `None`
+/// if the cell is not passable.
+///
+/// Returns `None` if `goal` cannot be reached from `start`.
+pub fn dijkstra<T, C>(
+    grid: &Grid<T>,
+    start: Base2d<usize>,
+    goal: Base2d<usize>,
+    cost: C,
+) -> Option<Path>
+where
+    C: Fn(&T) -> Option<u64>,
+{
+    search(grid, start, goal, cost, |_| 0)
+}
+
+/// A* search using the manhattan distance to `goal` as heuristic. `cost`
+/// returns the cost of entering a cell, or `None` if the cell is not passable.
+///
+/// Returns `None` if `goal` cannot be reached from `start`.
+///
+/// # Assumptions
+///
+/// Every passable cell costs at least one, otherwise the heuristic may
+/// overestimate and the returned path may not be the shortest.
+pub fn astar<T, C>(
+    grid: &Grid<T>,
+    start: Base2d<usize>,
+    goal: Base2d<usize>,
+    cost: C,
+) -> Option<Path>
+where
+    C: Fn(&T) -> Option<u64>,
+{
+    search(grid, start, goal, cost, |p: Base2d<usize>| {
+        p.manhattan(goal) as u64
+    })
+}
+
+//--------------------------------------------------------------------
+// Flood Fill
+//--------------------------------------------------------------------
+
+/// Labels the connected components of the grid. Two orthogonally adjacent
+/// cells belong to the same component if `same(a, b)` is `true`.
+///
+/// Returns a grid of labels, numbered from zero in row major order of first
+/// appearance, and the number of components.
+pub fn components<T, F>(grid: &Grid<T>, same: F) -> (Grid<usize>, usize)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let at = |idx: usize| grid.get(idx % grid.len_x, idx / grid.len_x);
+    let mut labels: Vec<Option<usize>> = vec![None; grid.size()];
+    let mut count = 0;
+    let mut stack = Vec::new();
+
+    for first in 0..grid.size() {
+        if labels[first].is_some() {
+            continue;
+        }
+        labels[first] = Some(count);
+        stack.push(first);
+        while let Some(idx) = stack.pop() {
+            for next in neighbours(grid, idx) {
+                if labels[next].is_none() && same(at(idx), at(next)) {
+                    labels[next] = Some(count);
+                    stack.push(next);
+                }
+            }
+        }
+        count += 1;
+    }
+
+    let flat = labels.into_iter().flatten().collect();
+    (Grid::new_with_vec(grid.len_x, grid.len_y, flat), count)
+}
+
+//--------------------------------------------------------------------
+// Helpers
+//--------------------------------------------------------------------
+
+/// Best first search shared by `dijkstra` and `astar`.
+fn search<T, C, H>(
+    grid: &Grid<T>,
+    start: Base2d<usize>,
+    goal: Base2d<usize>,
+    cost: C,
+    heuristic: H,
+) -> Option<Path>
+where
+    C: Fn(&T) -> Option<u64>,
+    H: Fn(Base2d<usize>) -> u64,
+{
+    let start_idx = index(grid, start);
+    let goal_idx = index(grid, goal);
+    let mut prev: Vec<Option<usize>> = vec![None; grid.size()];
+    let mut dist: Vec<Option<u64>> = vec![None; grid.size()];
+    let mut heap = BinaryHeap::new();
+    dist[start_idx] = Some(0);
+    heap.push(Reverse((heuristic(start), 0, start_idx)));
+
+    while let Some(Reverse((_, d, idx))) = heap.pop() {
+        if idx == goal_idx {
+            break;
+        }
+        if dist[idx].is_some_and(|best| d > best) {
+            continue; // stale entry
+        }
+        for next in neighbours(grid, idx) {
+            let pos = Base2d::new(next % grid.len_x, next / grid.len_x);
+            if let Some(c) = cost(grid.get(pos.x, pos.y)) {
+                let nd = d + c;
+                if dist[next].is_none_or(|best| nd < best) {
+                    dist[next] = Some(nd);
+                    prev[next] = Some(idx);
+                    heap.push(Reverse((nd + heuristic(pos), nd, next)));
+                }
+            }
+        }
+    }
+
+    build_path(grid, &prev, dist[goal_idx]?, start_idx, goal_idx)
+}
+
+/// walks back the `prev` links from `goal` to `start`.
+fn build_path<T>(
+    grid: &Grid<T>,
+    prev: &[Option<usize>],
+    cost: u64,
+    start: usize,
+    goal: usize,
+) -> Option<Path> {
+    let mut steps = vec![goal];
+    let mut idx = goal;
+    while idx != start {
+        idx = prev[idx]?;
+        steps.push(idx);
+    }
+    let steps = steps
+        .into_iter()
+        .rev()
+        .map(|i| Base2d::new(i % grid.len_x, i / grid.len_x))
+        .collect();
+    Some(Path { cost, steps })
+}
+
+/// returns the flat index of `pos`.
+///
+/// # Panics
+///
+/// Panics if `pos` is out of bounds.
+fn index<T>(grid: &Grid<T>, pos: Base2d<usize>) -> usize {
+    assert!(
+        pos.x < grid.len_x && pos.y < grid.len_y,
+        "Position out of bounds."
+    );
+    grid.len_x * pos.y + pos.x
+}
+
+/// returns the flat indexes of the orthogonal neighbours of `idx`.
+fn neighbours<T>(grid: &Grid<T>, idx: usize) -> impl Iterator<Item = usize> {
+    let (len_x, len_y) = (grid.len_x, grid.len_y);
+    let (x, y) = (idx % len_x, idx / len_x);
+    let up = if y > 0 { Some(idx - len_x) } else { None };
+    let down = if y + 1 < len_y {
+        Some(idx + len_x)
+    } else {
+        None
+    };
+    let left = if x > 0 { Some(idx - 1) } else { None };
+    let right = if x + 1 < len_x { Some(idx + 1) } else { None };
+    vec![up, down, left, right].into_iter().flatten()
+}
+
+//--------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: &str = "\
+..#....
+.##.##.
+...#...
+.#...#.
+.#.#...";
+
+    fn parse(input: &str) -> Grid<char> {
+        let len_x = input.find('\n').unwrap();
+        let flat: Vec<char> = input.lines().flat_map(str::chars).collect();
+        Grid::new_with_vec(len_x, flat.len() / len_x, flat)
+    }
+
+    #[test]
+    fn test_bfs() {
+        let grid = parse(MAZE);
+        let path = bfs(&grid, Base2d::new(0, 0), Base2d::new(6, 0), |&c| c != '#').unwrap();
+        assert_eq!(path.cost, 12);
+        assert_eq!(path.steps.len(), 13);
+        assert_eq!(path.steps[0].tuple(), (0, 0));
+        assert_eq!(path.steps[12].tuple(), (6, 0));
+        assert!(bfs(&grid, Base2d::new(0, 0), Base2d::new(2, 0), |&c| c != '#').is_none());
+    }
+
+    #[test]
+    fn test_weighted() {
+        // 1 9 1
+        // 1 9 1
+        // 1 1 1
+        let grid = Grid::new_with_vec(3, 3, vec![1, 9, 1, 1, 9, 1, 1, 1, 1]);
+        let (start, goal) = (Base2d::new(0, 0), Base2d::new(2, 0));
+        let d = dijkstra(&grid, start, goal, |&c| Some(c)).unwrap();
+        let a = astar(&grid, start, goal, |&c| Some(c)).unwrap();
+        assert_eq!(d.cost, 6);
+        assert_eq!(a.cost, 6);
+        let blocked = dijkstra(&grid, start, goal, |&c| if c == 9 { Some(c) } else { None });
+        assert!(blocked.is_none());
+    }
+
+    #[test]
+    fn test_components() {
+        let grid = parse(MAZE);
+        let (labels, count) = components(&grid, |a, b| a == b);
+        assert_eq!(count, 7);
+        assert_eq!(labels.get(0, 0), labels.get(4, 3));
+        assert_ne!(labels.get(0, 0), labels.get(2, 0));
+        assert_eq!(labels.get(2, 0), labels.get(1, 1));
+    }
+}
@@ -1,19 +1,183 @@
-// todo: implement tests
+use std::{fmt, fs::File, io, io::prelude::*, iter, ops::Range, str, str::FromStr};
 
-use std::{fs::File, io, io::prelude::*, str::FromStr};
+use memmap2::Mmap;
+
+pub mod numbers;
+pub mod parse;
+pub mod scan;
+
+pub use crate::scan;
 
 //--------------------------------------------------------------------
 // Read Input
 //--------------------------------------------------------------------
 
-/// reads the whole file into a String.
+/// reads the whole file into a String, normalised by `normalise`.
 pub fn to_str(filename: &str) -> Result<String, io::Error> {
-    let mut file = File::open("inputs/".to_string() + filename).unwrap();
+    Ok(normalise(&to_str_raw(filename)?))
+}
+
+/// reads the whole file into a String, as is.
+pub fn to_str_raw(filename: &str) -> Result<String, io::Error> {
+    let mut file = File::open("inputs/".to_string() + filename)?;
     let mut s = String::new();
     file.read_to_string(&mut s)?;
     Ok(s)
 }
 
+/// Cleans up what usually comes along when an input is pasted from a browser:
+///
+/// - removes a leading byte order mark;
+///
+/// - converts `\r\n` line endings into `\n`;
+///
+/// - removes trailing blank lines, i.e. lines holding only whitespace, leaving
+///   a single `\n` at the end of a non empty input.
+pub fn normalise(input: &str) -> String {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut out = input.replace("\r\n", "\n");
+    out.truncate(content_len(&out));
+    let len = out.trim_end_matches(['\n', '\r']).len();
+    out.truncate(len);
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+/// Borrowed counterpart of `normalise`: removes a leading byte order mark and
+/// trailing blank lines. `\r\n` line endings cannot be fixed without a copy,
+/// they are left in place for `str::lines` to handle.
+pub fn trim_input(input: &str) -> &str {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    &input[..content_len(input)]
+}
+
+/// returns the length of `input` without its trailing blank lines, keeping
+/// the line terminator of the last non blank line. A line is blank if it only
+/// holds whitespace, as counted by `report`.
+fn content_len(input: &str) -> usize {
+    match input.rfind(|c: char| !c.is_whitespace()) {
+        Some(i) => input[i..].find('\n').map_or(input.len(), |j| i + j + 1),
+        None => 0,
+    }
+}
+
+//------------------------------
+// Memory Mapped Input
+//------------------------------
+
+/// An input file mapped into memory. Parsers borrow slices from it through
+/// `as_str`, so large inputs are never copied.
+///
+/// ```no_run
+/// use adv20::helpers::read::{self, Input};
+/// let input = Input::open("day10").unwrap();
+/// let joltages: Vec<u8> = read::lines_into_vec(input.as_str());
+/// ```
+pub struct Input {
+    map: Option<Mmap>, // empty files cannot be mapped
+    text: Range<usize>,
+}
+
+impl Input {
+    /// maps the file `filename` of the inputs folder.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file cannot be read or if it is not valid UTF-8.
+    pub fn open(filename: &str) -> Result<Input, io::Error> {
+        let file = File::open("inputs/".to_string() + filename)?;
+        if file.metadata()?.len() == 0 {
+            return Ok(Input {
+                map: None,
+                text: 0..0,
+            });
+        }
+        // Safety: the inputs folder is not modified while the program runs.
+        let map = unsafe { Mmap::map(&file)? };
+        let full =
+            str::from_utf8(&map).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let trimmed = trim_input(full);
+        let start = trimmed.as_ptr() as usize - full.as_ptr() as usize;
+        let text = start..start + trimmed.len();
+        Ok(Input {
+            map: Some(map),
+            text,
+        })
+    }
+
+    /// returns the contents of the file, trimmed by `trim_input`.
+    pub fn as_str(&self) -> &str {
+        let bytes = self.map.as_deref().unwrap_or_default();
+        // SAFETY: `open` checked that the mapped bytes are UTF-8 and `text` is
+        // the range of a `&str` sliced from them, so it lies on char boundaries.
+        unsafe { str::from_utf8_unchecked(&bytes[self.text.clone()]) }
+    }
+}
+
+//------------------------------
+// Sanity Report
+//------------------------------
+
+/// A summary of a raw input file, for telling a corrupt input from a bug.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct InputReport {
+    pub bytes: usize,
+    pub lines: usize,
+    /// empty lines, i.e. blank records in a line per record input or record
+    /// separators in a blank line separated one.
+    pub blank_records: usize,
+    pub trailing_blank_lines: usize,
+    pub max_line_len: usize,
+    pub non_ascii_bytes: usize,
+    pub crlf_lines: usize,
+    pub has_bom: bool,
+}
+
+/// reads the raw file and reports on its contents.
+pub fn check(filename: &str) -> Result<InputReport, io::Error> {
+    Ok(report(&to_str_raw(filename)?))
+}
+
+/// builds the `InputReport` of a raw `input`.
+pub fn report(input: &str) -> InputReport {
+    let has_bom = input.starts_with('\u{feff}');
+    let lines: Vec<&str> = input.lines().collect();
+    let trailing_blank_lines = lines
+        .iter()
+        .rev()
+        .take_while(|l| l.trim().is_empty())
+        .count();
+    InputReport {
+        bytes: input.len(),
+        lines: lines.len(),
+        blank_records: lines.iter().filter(|l| l.trim().is_empty()).count(),
+        trailing_blank_lines,
+        max_line_len: lines.iter().map(|l| l.chars().count()).max().unwrap_or(0),
+        non_ascii_bytes: input.bytes().filter(|b| !b.is_ascii()).count(),
+        crlf_lines: input.matches("\r\n").count(),
+        has_bom,
+    }
+}
+
+impl fmt::Display for InputReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Bytes: {}", self.bytes)?;
+        writeln!(f, "Lines: {}", self.lines)?;
+        writeln!(f, "Blank records: {}", self.blank_records)?;
+        writeln!(f, "Trailing blank lines: {}", self.trailing_blank_lines)?;
+        writeln!(f, "Longest line: {}", self.max_line_len)?;
+        writeln!(f, "Non ASCII bytes: {}", self.non_ascii_bytes)?;
+        writeln!(f, "CRLF line endings: {}", self.crlf_lines)?;
+        write!(
+            f,
+            "Byte order mark: {}",
+            if self.has_bom { "yes" } else { "no" }
+        )
+    }
+}
+
 //--------------------------------------------------------------------
 // Commom Parsing
 //--------------------------------------------------------------------
@@ -83,3 +247,129 @@ where
 {
     input.split(split_at).map(str::parse::<T>).flatten()
 }
+
+//------------------------------
+// Entries borrow from the input
+//------------------------------
+
+/// Like `FromStr`, but the parsed value may hold slices of the input instead
+/// of owned copies.
+pub trait FromStrRef<'a>: Sized {
+    type Err;
+
+    fn from_str_ref(s: &'a str) -> Result<Self, Self::Err>;
+}
+
+/// borrowing version of `lines_into_vec`.
+pub fn lines_into_vec_ref<'a, T: FromStrRef<'a>>(input: &'a str) -> Vec<T> {
+    input
+        .lines()
+        .filter_map(|s| T::from_str_ref(s).ok())
+        .collect()
+}
+
+/// borrowing version of `split_into_vec`.
+pub fn split_into_vec_ref<'a, T: FromStrRef<'a>>(input: &'a str, split_at: &str) -> Vec<T> {
+    input
+        .split(split_at)
+        .filter_map(|s| T::from_str_ref(s).ok())
+        .collect()
+}
+
+/// returns the records of an input where records are separated by one or
+/// more blank lines, as in a passport batch. Unlike splitting at `"\n\n"`,
+/// this works with `\r\n` line endings too, so it can be used on an `Input`.
+/// Records do not include the terminator of their last line.
+pub fn records(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input;
+    iter::from_fn(move || {
+        let mut start = None;
+        let mut pos = 0;
+        while pos < rest.len() {
+            let end = rest[pos..].find('\n').map_or(rest.len(), |i| pos + i + 1);
+            let blank = rest[pos..end].trim().is_empty();
+            match (blank, start) {
+                (false, None) => start = Some(pos),
+                (true, Some(s)) => {
+                    let record = &rest[s..pos];
+                    rest = &rest[end..];
+                    return Some(record.trim_end_matches(['\n', '\r']));
+                }
+                _ => {}
+            }
+            pos = end;
+        }
+        let record = &rest[start?..];
+        rest = "";
+        Some(record.trim_end_matches(['\n', '\r']))
+    })
+}
+
+/// borrowing version of `split_into_vec` for inputs made of `records`.
+pub fn records_into_vec_ref<'a, T: FromStrRef<'a>>(input: &'a str) -> Vec<T> {
+    records(input)
+        .filter_map(|s| T::from_str_ref(s).ok())
+        .collect()
+}
+
+//--------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASTED: &str = "\u{feff}1\r\n\r\n2\r\nçc\r\n\r\n\n";
+
+    #[test]
+    fn test_normalise() {
+        assert_eq!(normalise(PASTED), "1\n\n2\nçc\n");
+        assert_eq!(normalise("1\n2"), "1\n2\n");
+        assert_eq!(normalise("\n\n"), "");
+        // a trailing line of spaces is blank, spaces ending a record are kept
+        let spaced = "1  \n  \n\t\r\n";
+        assert_eq!(normalise(spaced), "1  \n");
+        assert_eq!(trim_input(spaced), "1  \n");
+        assert_eq!(report(spaced).trailing_blank_lines, 2);
+    }
+
+    #[test]
+    fn test_trim_input() {
+        assert_eq!(trim_input(PASTED), "1\r\n\r\n2\r\nçc\r\n");
+        assert_eq!(trim_input("1\n2"), "1\n2");
+        assert_eq!(trim_input("\n\n"), "");
+    }
+
+    #[test]
+    fn test_records() {
+        let crlf = "a b\r\nc\r\n\r\n\r\nd\r\n \r\ne\r\n";
+        assert_eq!(records(crlf).collect::<Vec<_>>(), ["a b\r\nc", "d", "e"]);
+        assert_eq!(records("\na\n\nb").collect::<Vec<_>>(), ["a", "b"]);
+        assert_eq!(records("\n \n").count(), 0);
+    }
+
+    #[test]
+    fn test_input() {
+        let input = Input::open("day10").unwrap();
+        assert_eq!(input.as_str(), to_str("day10").unwrap());
+    }
+
+    #[test]
+    fn test_report() {
+        let r = report(PASTED);
+        assert_eq!(
+            r,
+            InputReport {
+                bytes: PASTED.len(),
+                lines: 6,
+                blank_records: 3,
+                trailing_blank_lines: 2,
+                max_line_len: 2,
+                non_ascii_bytes: 5,
+                crlf_lines: 5,
+                has_bom: true,
+            }
+        );
+    }
+}
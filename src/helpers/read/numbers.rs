@@ -0,0 +1,248 @@
+//! Parsing of lists of integers.
+//!
+//! Unlike `lines_into_vec`, which needs one value per line and silently drops
+//! what does not parse, the functions here:
+//!
+//! - detect whether values are separated by commas, new lines or any
+//!   whitespace;
+//!
+//! - accept an explicit `+` sign, as in "jmp +4";
+//!
+//! - accept the `0x`, `0o` and `0b` prefixes for hexadecimal, octal and binary
+//!   values, or a radix given by the caller;
+//!
+//! - report the line and column of the first value that fails to parse, and
+//!   whether it overflowed the target type.
+//!
+//! ```
+//! use adv20::helpers::read::numbers;
+//! assert_eq!(numbers::list::<i16>("+4, -0x10,0b11").unwrap(), vec![4, -16, 3]);
+//! let err = numbers::list::<u8>("1\n2\n300").unwrap_err();
+//! assert_eq!((err.line, err.column, err.overflow()), (3, 1, true));
+//! ```
+
+use std::{
+    error::Error,
+    fmt,
+    num::{IntErrorKind, ParseIntError},
+};
+
+/// How the values of a list are separated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Separator {
+    /// commas, surrounding whitespace and new lines are ignored, as is a
+    /// trailing comma. Empty values between two commas are rejected.
+    Comma,
+    /// one value per line, blank lines are ignored.
+    Newline,
+    /// any whitespace.
+    Whitespace,
+}
+
+/// returns the separator used by `input`: commas if there is any, new lines if
+/// there is more than one non blank line, whitespace otherwise.
+pub fn detect_separator(input: &str) -> Separator {
+    if input.contains(',') {
+        Separator::Comma
+    } else if input.lines().filter(|l| !l.trim().is_empty()).count() > 1 {
+        Separator::Newline
+    } else {
+        Separator::Whitespace
+    }
+}
+
+/// parses a list of integers, detecting the separator and the radix of each
+/// value from its prefix.
+pub fn list<T: Integer>(input: &str) -> Result<Vec<T>, NumberError> {
+    parse_all(input, None)
+}
+
+/// parses a list of integers written in `radix`, detecting the separator.
+/// Prefixes are not accepted.
+///
+/// # Panics
+///
+/// Panics if `radix` is not in the range from 2 to 36.
+pub fn list_radix<T: Integer>(input: &str, radix: u32) -> Result<Vec<T>, NumberError> {
+    parse_all(input, Some(radix))
+}
+
+//--------------------------------------------------------------------
+// Integer Types
+//--------------------------------------------------------------------
+
+/// Integer types that can be parsed in any radix.
+pub trait Integer: Sized {
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseIntError>;
+}
+
+macro_rules! integer {
+    ($($t:ty),*) => {$(
+        impl Integer for $t {
+            fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseIntError> {
+                <$t>::from_str_radix(s, radix)
+            }
+        }
+    )*};
+}
+
+integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+//--------------------------------------------------------------------
+// Helpers
+//--------------------------------------------------------------------
+
+fn parse_all<T: Integer>(input: &str, radix: Option<u32>) -> Result<Vec<T>, NumberError> {
+    let tokens: Vec<&str> = match detect_separator(input) {
+        Separator::Comma => {
+            let mut tokens: Vec<&str> = input.split(',').map(str::trim).collect();
+            if tokens.last() == Some(&"") {
+                tokens.pop();
+            }
+            tokens
+        }
+        Separator::Newline => input
+            .lines()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .collect(),
+        Separator::Whitespace => input.split_whitespace().collect(),
+    };
+    tokens
+        .into_iter()
+        .map(|token| parse_one(token, radix).map_err(|e| NumberError::new(input, token, e)))
+        .collect()
+}
+
+/// parses a single value, handling the sign and the radix prefix.
+fn parse_one<T: Integer>(token: &str, radix: Option<u32>) -> Result<T, ParseIntError> {
+    let (sign, digits) = match token.as_bytes().first() {
+        Some(b'+') | Some(b'-') => token.split_at(1),
+        _ => ("", token),
+    };
+    let (radix, digits) = match radix {
+        Some(r) => (r, digits),
+        None => match digits.get(..2) {
+            Some("0x") | Some("0X") => (16, &digits[2..]),
+            Some("0o") | Some("0O") => (8, &digits[2..]),
+            Some("0b") | Some("0B") => (2, &digits[2..]),
+            _ => (10, digits),
+        },
+    };
+    if digits.starts_with(['+', '-']) {
+        // a sign after the prefix, or a second sign: parsing the whole token
+        // reports the invalid digit
+        return T::from_str_radix(token, radix);
+    }
+    // an unsigned type rejects the '-' sign itself, so "-0" is not accepted
+    T::from_str_radix(&format!("{}{}", sign, digits), radix)
+}
+
+//--------------------------------------------------------------------
+// Errors
+//--------------------------------------------------------------------
+
+/// Error locating the first value of a list that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberError {
+    /// line of the value, starting at one.
+    pub line: usize,
+    /// column of the value, in characters, starting at one.
+    pub column: usize,
+    /// the offending value.
+    pub token: String,
+    /// the reason reported by the integer parser.
+    pub source: ParseIntError,
+}
+
+impl NumberError {
+    /// builds the error for `token`, a slice of `input`.
+    fn new(input: &str, token: &str, source: ParseIntError) -> Self {
+        let offset = token.as_ptr() as usize - input.as_ptr() as usize;
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        NumberError {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            token: token.to_owned(),
+            source,
+        }
+    }
+
+    /// returns `true` if the value is a valid number that does not fit in the
+    /// target type.
+    pub fn overflow(&self) -> bool {
+        matches!(
+            self.source.kind(),
+            IntErrorKind::PosOverflow | IntErrorKind::NegOverflow
+        )
+    }
+}
+
+impl Error for NumberError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+impl fmt::Display for NumberError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: invalid value '{}': {}.",
+            self.line, self.column, self.token, self.source
+        )
+    }
+}
+
+//--------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_separators() {
+        assert_eq!(detect_separator("1,2\n3"), Separator::Comma);
+        assert_eq!(detect_separator("1\n2\n"), Separator::Newline);
+        assert_eq!(detect_separator("1 2 3\n"), Separator::Whitespace);
+        assert_eq!(list::<u32>("1, 2,\n3").unwrap(), vec![1, 2, 3]);
+        assert_eq!(list::<u32>("1,2,\n").unwrap(), vec![1, 2]);
+        assert_eq!(list::<u32>("1,,2").unwrap_err().column, 3);
+        assert_eq!(list::<u32>("16\n10\n\n15\n").unwrap(), vec![16, 10, 15]);
+        assert_eq!(list::<u32>(" 7  8\t9").unwrap(), vec![7, 8, 9]);
+    }
+
+    #[test]
+    fn test_signs_and_radix() {
+        assert_eq!(list::<i16>("+0 -3 +4").unwrap(), vec![0, -3, 4]);
+        assert_eq!(list::<u8>("0xff 0o17 0b101").unwrap(), vec![255, 15, 5]);
+        assert_eq!(list::<i8>("-0x80").unwrap(), vec![-128]);
+        for token in &["0x-5", "0x+5", "-0x-5", "+-5", "0b+1"] {
+            assert_eq!(list::<i16>(token).unwrap_err().token, *token);
+        }
+        assert!(list_radix::<i16>("+-5", 10).is_err());
+        assert!(list_radix::<u16>("FBFBBFF\nBFFFBBF", 2).is_err());
+        assert_eq!(list_radix::<u16>("1011\n111", 2).unwrap(), vec![11, 7]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = list::<u8>("1, 2, 256").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 7));
+        assert!(err.overflow());
+        assert_eq!(err.token, "256");
+
+        let err = list::<i16>("acc +1\njmp -3").unwrap_err();
+        assert!(!err.overflow());
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 1: invalid value 'acc +1': invalid digit found in string."
+        );
+
+        let err = list::<u32>("1\n-2").unwrap_err();
+        assert_eq!((err.line, err.column, err.overflow()), (2, 1, false));
+    }
+}
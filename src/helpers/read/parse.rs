@@ -0,0 +1,401 @@
+//! Small combinator-style parsing over string slices.
+//!
+//! A `Cursor` walks an input string. Each parsing method consumes what it
+//! recognises and returns it, or leaves the cursor untouched and returns a
+//! `ParseError` telling where the input did not match and what was expected.
+//! Parsers for records are written as plain functions taking a `&mut Cursor`,
+//! which can then be combined with `separated`, `attempt` or `lines`. Such
+//! functions may stop halfway through on failure, unless run by `attempt` or
+//! `separated`, which rewind them.
+//!
+//! ```
+//! use adv20::helpers::read::parse::{Cursor, ParseError};
+//!
+//! // "1-3 a: abcde"
+//! fn record<'a>(c: &mut Cursor<'a>) -> Result<(u8, u8, char, &'a str), ParseError> {
+//!     let lower = c.integer()?;
+//!     c.literal("-")?;
+//!     let upper = c.integer()?;
+//!     c.literal(" ")?;
+//!     let ch = c.any_char()?;
+//!     c.literal(": ")?;
+//!     Ok((lower, upper, ch, c.word()?))
+//! }
+//!
+//! let mut cursor = Cursor::new("1-3 a: abcde");
+//! assert_eq!(record(&mut cursor).unwrap(), (1, 3, 'a', "abcde"));
+//! ```
+
+use std::{error::Error, fmt, str::FromStr};
+
+//--------------------------------------------------------------------
+// Cursor
+//--------------------------------------------------------------------
+
+/// A position inside an input string slice.
+#[derive(Debug, Clone, Copy)]
+pub struct Cursor<'a> {
+    input: &'a str,
+    pos: usize,
+    first_line: usize,
+}
+
+impl<'a> Cursor<'a> {
+    /// Creates a cursor at the beginning of `input`.
+    pub fn new(input: &'a str) -> Self {
+        Cursor::at_line(input, 1)
+    }
+
+    /// Creates a cursor at the beginning of `input`, reporting errors as if
+    /// `input` started at line `line` of a larger text.
+    pub fn at_line(input: &'a str, line: usize) -> Self {
+        Cursor {
+            input,
+            pos: 0,
+            first_line: line,
+        }
+    }
+
+    /// returns the input not consumed yet.
+    pub fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    /// returns `true` if the whole input has been consumed.
+    pub fn is_empty(&self) -> bool {
+        self.pos == self.input.len()
+    }
+
+    //------------------------------
+    // Primitives
+    //------------------------------
+
+    /// consumes exactly `lit`.
+    pub fn literal(&mut self, lit: &str) -> Result<(), ParseError> {
+        if self.rest().starts_with(lit) {
+            self.pos += lit.len();
+            Ok(())
+        } else {
+            Err(self.error(format!("'{}'", lit)))
+        }
+    }
+
+    /// consumes a single character, whatever it is.
+    pub fn any_char(&mut self) -> Result<char, ParseError> {
+        let c = self
+            .rest()
+            .chars()
+            .next()
+            .ok_or_else(|| self.error("a character"))?;
+        self.pos += c.len_utf8();
+        Ok(c)
+    }
+
+    /// consumes the longest, possibly empty, prefix whose characters satisfy
+    /// `predicate`.
+    pub fn take_while<P>(&mut self, predicate: P) -> &'a str
+    where
+        P: Fn(char) -> bool,
+    {
+        let rest = self.rest();
+        let len = rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    /// consumes any whitespace, new lines included.
+    pub fn whitespace(&mut self) {
+        self.take_while(char::is_whitespace);
+    }
+
+    /// consumes a non empty run of alphanumeric characters.
+    pub fn word(&mut self) -> Result<&'a str, ParseError> {
+        self.non_empty(char::is_alphanumeric, "a word")
+    }
+
+    /// consumes a non empty run of non whitespace characters.
+    pub fn token(&mut self) -> Result<&'a str, ParseError> {
+        self.non_empty(|c| !c.is_whitespace(), "a token")
+    }
+
+    /// consumes an integer with an optional `+` or `-` sign and parses it.
+    pub fn integer<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        let start = *self;
+        let sign = match self.rest().chars().next() {
+            Some(c @ '+') | Some(c @ '-') => c.len_utf8(),
+            _ => 0,
+        };
+        let digits = self.rest()[sign..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest().len() - sign);
+        if digits == 0 {
+            return Err(self.error("an integer"));
+        }
+        let text = &self.rest()[..sign + digits];
+        match text.parse() {
+            Ok(v) => {
+                self.pos += text.len();
+                Ok(v)
+            }
+            Err(_) => {
+                *self = start;
+                Err(self.error("an integer in range"))
+            }
+        }
+    }
+
+    /// consumes a token and parses it with `FromStr`, e.g. a vertex name or a
+    /// weight. The cursor is left untouched if the token does not parse.
+    pub fn value<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        let start = *self;
+        match self.token()?.parse() {
+            Ok(v) => Ok(v),
+            Err(_) => {
+                *self = start;
+                Err(self.error("a valid value"))
+            }
+        }
+    }
+
+    /// succeeds only if the whole input has been consumed.
+    pub fn end(&self) -> Result<(), ParseError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error("end of input"))
+        }
+    }
+
+    /// builds an error at the current position, for parsers needing checks
+    /// the methods above do not provide.
+    pub fn error<S: Into<String>>(&self, expected: S) -> ParseError {
+        let before = &self.input[..self.pos];
+        let line = self.first_line + before.matches('\n').count();
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        ParseError {
+            line,
+            column: before[line_start..].chars().count() + 1,
+            expected: expected.into(),
+            found: self.rest().chars().take(10).collect(),
+        }
+    }
+
+    //------------------------------
+    // Combinators
+    //------------------------------
+
+    /// runs `parser` and rewinds the cursor if it fails, so an alternative can
+    /// be tried from the same position.
+    pub fn attempt<T, F>(&mut self, parser: F) -> Option<T>
+    where
+        F: FnOnce(&mut Cursor<'a>) -> Result<T, ParseError>,
+    {
+        let start = *self;
+        match parser(self) {
+            Ok(v) => Some(v),
+            Err(_) => {
+                *self = start;
+                None
+            }
+        }
+    }
+
+    /// parses one or more `item`s separated by the literal `sep`. If an item
+    /// fails, even after a separator, the whole list is rewound.
+    pub fn separated<T, F>(&mut self, sep: &str, mut item: F) -> Result<Vec<T>, ParseError>
+    where
+        F: FnMut(&mut Cursor<'a>) -> Result<T, ParseError>,
+    {
+        self.rewinding(|c| {
+            let mut out = vec![item(c)?];
+            while c.attempt(|c| c.literal(sep)).is_some() {
+                out.push(item(c)?);
+            }
+            Ok(out)
+        })
+    }
+
+    /// parses whitespace separated `key<sep>value` pairs until the end of the
+    /// input, e.g. `"ecl:gry pid:860033327"` with `sep = ":"`. Keys are words
+    /// and values any token. If a pair fails, all of them are rewound.
+    pub fn key_values(&mut self, sep: &str) -> Result<Vec<(&'a str, &'a str)>, ParseError> {
+        self.rewinding(|c| {
+            let mut out = Vec::new();
+            c.whitespace();
+            while !c.is_empty() {
+                let key = c.word()?;
+                c.literal(sep)?;
+                let value = c.token()?;
+                out.push((key, value));
+                c.whitespace();
+            }
+            Ok(out)
+        })
+    }
+
+    //------------------------------
+    // Helpers
+    //------------------------------
+
+    /// runs `parser` and rewinds the cursor if it fails. Unlike `attempt`, the
+    /// error is kept, pointing where the input did not match.
+    fn rewinding<T, F>(&mut self, parser: F) -> Result<T, ParseError>
+    where
+        F: FnOnce(&mut Cursor<'a>) -> Result<T, ParseError>,
+    {
+        let start = *self;
+        let parsed = parser(self);
+        if parsed.is_err() {
+            *self = start;
+        }
+        parsed
+    }
+
+    fn non_empty<P>(&mut self, predicate: P, expected: &str) -> Result<&'a str, ParseError>
+    where
+        P: Fn(char) -> bool,
+    {
+        match self.take_while(predicate) {
+            "" => Err(self.error(expected)),
+            s => Ok(s),
+        }
+    }
+}
+
+/// Runs `parser` on every line of `input`, requiring each line to be fully
+/// consumed. Errors report the line number inside `input`.
+pub fn lines<'a, T, F>(input: &'a str, mut parser: F) -> Result<Vec<T>, ParseError>
+where
+    F: FnMut(&mut Cursor<'a>) -> Result<T, ParseError>,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let mut cursor = Cursor::at_line(line, i + 1);
+            let v = parser(&mut cursor)?;
+            cursor.end()?;
+            Ok(v)
+        })
+        .collect()
+}
+
+//--------------------------------------------------------------------
+// Errors
+//--------------------------------------------------------------------
+
+/// Error describing where, and why, the input did not match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// line number, starting at one.
+    pub line: usize,
+    /// column number, in characters, starting at one.
+    pub column: usize,
+    /// description of what the parser was looking for.
+    pub expected: String,
+    /// the beginning of the remaining input.
+    pub found: String,
+}
+
+impl Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, found '{}'.",
+            self.line, self.column, self.expected, self.found
+        )
+    }
+}
+
+//--------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_primitives() {
+        let mut c = Cursor::new("acc +12 jmp -3");
+        assert_eq!(c.word().unwrap(), "acc");
+        c.whitespace();
+        assert_eq!(c.integer::<i16>().unwrap(), 12);
+        assert!(c.literal(" nop").is_err());
+        c.literal(" jmp ").unwrap();
+        assert_eq!(c.integer::<i16>().unwrap(), -3);
+        assert!(c.end().is_ok());
+    }
+
+    #[test]
+    fn test_value() {
+        let mut c = Cursor::new("2.5 x");
+        assert_eq!(c.value::<f32>().unwrap(), 2.5);
+        c.whitespace();
+        let err = c.value::<f32>().unwrap_err();
+        assert_eq!((err.column, err.expected.as_str()), (5, "a valid value"));
+        assert_eq!(c.rest(), "x");
+    }
+
+    #[test]
+    fn test_integer_overflow() {
+        let mut c = Cursor::new("300");
+        let err = c.integer::<u8>().unwrap_err();
+        assert_eq!(err.expected, "an integer in range");
+        assert_eq!(c.rest(), "300");
+    }
+
+    #[test]
+    fn test_combinators() {
+        let mut c = Cursor::new("1 red, 22 blue.");
+        let items = c
+            .separated(", ", |c| {
+                let n: u32 = c.integer()?;
+                c.literal(" ")?;
+                Ok((n, c.word()?))
+            })
+            .unwrap();
+        assert_eq!(items, vec![(1, "red"), (22, "blue")]);
+        assert!(c.attempt(|c| c.literal("!")).is_none());
+        assert_eq!(c.rest(), ".");
+
+        // fails after consuming a separator
+        let mut c = Cursor::new("1,2,x");
+        let err = c.separated(",", |c| c.integer::<u8>()).unwrap_err();
+        assert_eq!(err.column, 5);
+        assert_eq!(c.rest(), "1,2,x");
+    }
+
+    #[test]
+    fn test_key_values() {
+        let mut c = Cursor::new("ecl:gry pid:860033327\nhcl:#fffffd");
+        let pairs = c.key_values(":").unwrap();
+        assert_eq!(
+            pairs,
+            vec![("ecl", "gry"), ("pid", "860033327"), ("hcl", "#fffffd")]
+        );
+
+        // fails after consuming a key
+        let mut c = Cursor::new("ecl:gry pid");
+        let err = c.key_values(":").unwrap_err();
+        assert_eq!((err.column, err.expected.as_str()), (12, "':'"));
+        assert_eq!(c.rest(), "ecl:gry pid");
+    }
+
+    #[test]
+    fn test_error_position() {
+        let err = lines("1,2\n3,x", |c| c.separated(",", |c| c.integer::<u8>())).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 3: expected an integer, found 'x'."
+        );
+
+        let mut c = Cursor::new("a\nbc d");
+        c.take_while(|c| c != 'd');
+        let err = c.end().unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+    }
+}
@@ -0,0 +1,259 @@
+//! Scanf-style extraction of typed fields from one-line records.
+//!
+//! A pattern is a string where every `{}` marks a field, everything else must
+//! appear literally in the input. A field may be named by writing the name
+//! inside the braces, e.g. `{lower}`, which is then used in error messages.
+//! Unnamed fields are reported by their position, starting at one.
+//!
+//! Fields extend up to the first occurrence of the literal text following them
+//! in the pattern, so two fields must always be separated by some literal.
+//!
+//! ```
+//! use adv20::helpers::read;
+//! let (lower, upper, ch, pass) =
+//!     read::scan!("1-3 a: abcde", "{}-{} {}: {}", u8, u8, char, String).unwrap();
+//! assert_eq!((lower, upper, ch, pass.as_str()), (1, 3, 'a', "abcde"));
+//!
+//! let err = read::scan!("acc +x", "{op} {arg}", String, i16).unwrap_err();
+//! assert_eq!(err.field, "arg");
+//! ```
+
+use std::{borrow::Cow, error::Error, fmt, str::FromStr};
+
+/// Extracts the fields described by a pattern from a string slice and parses
+/// each one into the listed types, returning them as a tuple.
+///
+/// `scan!(input, pattern, T1, T2, ...)` evaluates to
+/// `Result<(T1, T2, ...), ScanError>`.
+#[macro_export]
+macro_rules! scan {
+    ($input:expr, $pattern:expr, $($t:ty),+ $(,)?) => {
+        (|| -> ::std::result::Result<_, $crate::helpers::read::scan::ScanError> {
+            let count = [$(stringify!($t)),+].len();
+            let mut fields = $crate::helpers::read::scan::fields($input, $pattern, count)?;
+            Ok(($($crate::helpers::read::scan::field::<$t>(&fields.next().unwrap()?)?,)+))
+        })()
+    };
+}
+
+/// A field extracted from the input, before parsing. Its name is borrowed
+/// from the pattern, unless the field is unnamed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field<'a> {
+    pub name: Cow<'a, str>,
+    pub value: &'a str,
+}
+
+/// Iterator over the raw fields of an input, created by `fields`. Nothing is
+/// allocated while scanning named fields.
+#[derive(Debug, Clone)]
+pub struct Fields<'a> {
+    /// input left after the last field and the literal following it.
+    input: &'a str,
+    /// pattern left, starting at the next field.
+    pattern: &'a str,
+    /// position of the last field returned.
+    position: usize,
+}
+
+/// Checks that every `{` of `pattern` is closed, that it holds exactly `count`
+/// fields and that `input` starts with its leading literal, then returns the
+/// raw fields one by one. Each one fails if the literal following it in the
+/// pattern is not found.
+pub fn fields<'a>(input: &'a str, pattern: &'a str, count: usize) -> Result<Fields<'a>, ScanError> {
+    let mut found = 0;
+    let mut rest = split_literal(pattern).1;
+    while !rest.is_empty() {
+        match rest[1..].find(['{', '}']) {
+            Some(close) if rest[1 + close..].starts_with('}') => {
+                found += 1;
+                rest = split_literal(&rest[close + 2..]).1;
+            }
+            _ => {
+                let reason = String::from("unclosed '{'");
+                return Err(ScanError::new("pattern", pattern, reason));
+            }
+        }
+    }
+    if found != count {
+        return Err(ScanError::new(
+            "pattern",
+            pattern,
+            format!("{} fields in the pattern, {} types given", found, count),
+        ));
+    }
+
+    let (first, pattern) = split_literal(pattern);
+    let input = input
+        .strip_prefix(first)
+        .ok_or_else(|| ScanError::new("input", input, format!("to start with '{}'", first)))?;
+    Ok(Fields {
+        input,
+        pattern,
+        position: 0,
+    })
+}
+
+impl<'a> Iterator for Fields<'a> {
+    type Item = Result<Field<'a>, ScanError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pattern = self.pattern;
+        if pattern.is_empty() {
+            return None;
+        }
+        // `fields` checked that every field is closed
+        let close = pattern.find('}').unwrap();
+        self.position += 1;
+        let name = match &pattern[1..close] {
+            "" => Cow::Owned(self.position.to_string()),
+            name => Cow::Borrowed(name),
+        };
+        let (lit, pattern) = split_literal(&pattern[close + 1..]);
+        self.pattern = pattern;
+
+        let rest = self.input;
+        let end = if pattern.is_empty() {
+            rest.strip_suffix(lit).map(str::len)
+        } else if lit.is_empty() {
+            None
+        } else {
+            rest.find(lit)
+        };
+        match end {
+            Some(end) => {
+                self.input = &rest[end + lit.len()..];
+                Some(Ok(Field {
+                    name,
+                    value: &rest[..end],
+                }))
+            }
+            None => {
+                self.pattern = "";
+                let reason = format!("to be followed by '{}'", lit);
+                Some(Err(ScanError::new(&name, rest, reason)))
+            }
+        }
+    }
+}
+
+/// parses a single field.
+pub fn field<T>(field: &Field) -> Result<T, ScanError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    field
+        .value
+        .parse()
+        .map_err(|e: T::Err| ScanError::new(&field.name, field.value, e.to_string()))
+}
+
+/// returns the literal text at the start of `pattern`, up to the next field,
+/// and the rest of the pattern.
+fn split_literal(pattern: &str) -> (&str, &str) {
+    pattern.split_at(pattern.find('{').unwrap_or(pattern.len()))
+}
+
+//--------------------------------------------------------------------
+// Errors
+//--------------------------------------------------------------------
+
+/// Error naming the field that could not be extracted or parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanError {
+    /// name, or position, of the field.
+    pub field: String,
+    /// the offending text.
+    pub value: String,
+    /// why it failed.
+    pub reason: String,
+}
+
+impl ScanError {
+    fn new(field: &str, value: &str, reason: String) -> Self {
+        ScanError {
+            field: field.to_owned(),
+            value: value.to_owned(),
+            reason,
+        }
+    }
+}
+
+impl Error for ScanError {}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Could not scan field {} from '{}': {}.",
+            self.field, self.value, self.reason
+        )
+    }
+}
+
+//--------------------------------------------------------------------
+// Tests
+//--------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan() {
+        let (a, b) = scan!("move 3 to 7.", "move {} to {}.", u32, u64).unwrap();
+        assert_eq!((a, b), (3, 7));
+        let (op,) = scan!("nop", "{}", String).unwrap();
+        assert_eq!(op, "nop");
+    }
+
+    #[test]
+    fn test_fields() {
+        let mut scanned = fields("1-3 a", "{lower}-{} {ch}", 3).unwrap();
+        let lower = scanned.next().unwrap().unwrap();
+        assert_eq!(lower.value, "1");
+        assert!(matches!(lower.name, Cow::Borrowed("lower")));
+        assert_eq!(scanned.next().unwrap().unwrap().name, "2");
+        assert_eq!(scanned.next().unwrap().unwrap().value, "a");
+        assert!(scanned.next().is_none());
+
+        let mut scanned = fields("1 3", "{}-{}", 2).unwrap();
+        assert_eq!(scanned.next().unwrap().unwrap_err().field, "1");
+        assert!(scanned.next().is_none());
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = scan!(
+            "1-3 a: abcde",
+            "{lower}-{upper} {ch}: {pass}",
+            u8,
+            u8,
+            u8,
+            String
+        )
+        .unwrap_err();
+        assert_eq!(err.field, "ch");
+        assert_eq!(err.value, "a");
+
+        let err = scan!("1 3", "{}-{}", u8, u8).unwrap_err();
+        assert_eq!(err.field, "1");
+        assert_eq!(err.reason, "to be followed by '-'");
+
+        let err = scan!("1-3", "{}-{}", u8).unwrap_err();
+        assert_eq!(err.field, "pattern");
+
+        for pattern in &["{lower-{upper}", "{}-{", "{a}{b{c}"] {
+            let err = fields("1-3", pattern, 2).unwrap_err();
+            assert_eq!(err.field, "pattern");
+            assert_eq!(err.reason, "unclosed '{'");
+        }
+
+        let err = scan!("x: 1", "y: {}", u8).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Could not scan field input from 'x: 1': to start with 'y: '."
+        );
+    }
+}
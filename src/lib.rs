@@ -13,7 +13,13 @@ pub mod day10;
 
 pub mod helpers {
     pub mod base2d;
+    pub mod bitgrid;
+    pub mod direction;
     pub mod graph;
     pub mod grid;
+    pub mod gridnd;
+    pub mod image;
+    pub mod infinite_grid;
+    pub mod pathfinding;
     pub mod read;
 }
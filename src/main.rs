@@ -1,5 +1,5 @@
-use adv20::*;
-use std::env;
+use adv20::{helpers::read, *};
+use std::{env, process};
 
 fn main() {
     let input = env::args().nth(1);
@@ -8,7 +8,31 @@ fn main() {
         return;
     }
 
-    match input.unwrap().parse().unwrap() {
+    let day: u8 = input.unwrap().parse().unwrap();
+
+    // `--check-input` reports on the raw input file instead of solving the day
+    if env::args().skip(2).any(|arg| arg == "--check-input") {
+        let filename = format!("day{:02}", day);
+        match read::check(&filename) {
+            Ok(report) => println!("Input {}\n{}", filename, report),
+            Err(e) => println!("Unable to read input {}: {}", filename, e),
+        }
+        return;
+    }
+
+    // `--dot` prints the graph of the graph based days in the DOT language
+    if env::args().skip(2).any(|arg| arg == "--dot") {
+        match day {
+            7 => print!("{}", day07::dot()),
+            _ => {
+                eprintln!("Day {} has no graph.", day);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    match day {
         1 => day01::run(),
         2 => day02::run(),
         3 => day03::run(),